@@ -0,0 +1,71 @@
+use amethyst::assets::PrefabLoaderSystemDesc;
+use amethyst::{
+    core::frame_limiter::FrameRateLimitStrategy, core::transform::TransformBundle, prelude::*,
+    utils::application_root_dir,
+};
+use std::env;
+
+use evolution_island::components::{combat, creatures};
+use evolution_island::states::headless::HeadlessLoadingState;
+
+/// Simulated seconds to run for when no `--seconds` argument is given.
+const DEFAULT_DURATION: f32 = 60.0;
+
+// Runs the ecosystem without a window, renderer, UI or audio, for a given number of simulated
+// seconds. Usage: `evoli-sim [--seconds <seconds>] [--seed <seed>]`
+fn main() -> amethyst::Result<()> {
+    amethyst::start_logger(Default::default());
+
+    let (duration, seed) = parse_arguments(env::args().skip(1));
+
+    let resources = application_root_dir()
+        .unwrap()
+        .into_os_string()
+        .into_string()
+        .unwrap()
+        + "/resources";
+
+    let game_data = GameDataBuilder::default()
+        .with_bundle(TransformBundle::new())?
+        .with_system_desc(
+            PrefabLoaderSystemDesc::<creatures::CreaturePrefabData>::default(),
+            "creature_loader",
+            &[],
+        )
+        .with_system_desc(
+            PrefabLoaderSystemDesc::<combat::FactionPrefabData>::default(),
+            "",
+            &[],
+        );
+
+    let mut simulation: Application<GameData> = CoreApplication::build(
+        resources.clone(),
+        HeadlessLoadingState::new(resources, duration, seed),
+    )?
+    .with_frame_limit(FrameRateLimitStrategy::Unlimited, 0)
+    .build(game_data)?;
+    simulation.run();
+    Ok(())
+}
+
+fn parse_arguments(mut args: impl Iterator<Item = String>) -> (f32, Option<u64>) {
+    let mut duration = DEFAULT_DURATION;
+    let mut seed = None;
+    while let Some(arg) = args.next() {
+        if arg == "--seconds" {
+            duration = args
+                .next()
+                .and_then(|value| value.parse().ok())
+                .expect("--seconds expects a number of simulated seconds");
+        } else if arg == "--seed" {
+            seed = Some(
+                args.next()
+                    .and_then(|value| value.parse().ok())
+                    .expect("--seed expects an unsigned 64 bit number"),
+            );
+        } else {
+            eprintln!("Ignoring unknown argument {:?}", arg);
+        }
+    }
+    (duration, seed)
+}
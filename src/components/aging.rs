@@ -0,0 +1,89 @@
+use amethyst::{
+    assets::PrefabData,
+    core::math::Vector3,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// The stages a creature goes through as it ages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum LifeStage {
+    Juvenile,
+    Adult,
+    Elder,
+}
+
+/// How a life stage changes a creature, relative to an adult of its kind.
+#[derive(Clone, Debug, Deserialize, Serialize, smart_default::SmartDefault)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct StageModifiers {
+    #[default(1.0)]
+    pub scale: f32,
+    /// Multiplies `Movement::max_movement_speed`.
+    #[default(1.0)]
+    pub speed: f32,
+    /// Multiplies `Damage::damage`.
+    #[default(1.0)]
+    pub damage: f32,
+}
+
+/// What the creature is like as an adult, taken when the `AgingSystem` first sees it. The prefab
+/// and the genome describe adults; every life stage is applied on top of these values.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AdultTraits {
+    pub scale: Vector3<f32>,
+    pub max_movement_speed: Option<f32>,
+    pub damage: Option<f32>,
+}
+
+/// Makes a creature grow up, grow old and eventually die of old age, see `AgingSystem`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+pub struct Age {
+    /// Seconds the creature has been alive.
+    pub seconds: f32,
+    /// Every creature lives between `min_lifespan` and `max_lifespan` seconds.
+    pub min_lifespan: f32,
+    pub max_lifespan: f32,
+    /// Share of its lifespan after which a juvenile becomes an adult.
+    pub adult_at: f32,
+    /// Share of its lifespan after which an adult becomes an elder.
+    pub elder_at: f32,
+    pub juvenile: StageModifiers,
+    pub elder: StageModifiers,
+    /// The lifespan of this particular creature, picked when it is born.
+    pub lifespan: Option<f32>,
+    /// The stage currently applied to the creature.
+    pub stage: Option<LifeStage>,
+    pub adult_traits: Option<AdultTraits>,
+}
+
+impl Component for Age {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Age {
+    /// The life stage of a creature of this age with the given lifespan.
+    pub fn stage_at(&self, lifespan: f32) -> LifeStage {
+        let share = self.seconds / lifespan;
+        if share < self.adult_at {
+            LifeStage::Juvenile
+        } else if share < self.elder_at {
+            LifeStage::Adult
+        } else {
+            LifeStage::Elder
+        }
+    }
+
+    pub fn modifiers(&self, stage: LifeStage) -> StageModifiers {
+        match stage {
+            LifeStage::Juvenile => self.juvenile.clone(),
+            LifeStage::Adult => StageModifiers::default(),
+            LifeStage::Elder => self.elder.clone(),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::components::utility::Behavior;
+
+/// Lets a behaviour tree drive the creature instead of the default steering systems, see
+/// `BehaviorTreeSystem`. The tree is looked up by name in the `BehaviorTrees`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(deny_unknown_fields)]
+pub struct BehaviorTree {
+    pub name: String,
+}
+
+impl Component for BehaviorTree {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// What a creature can look out for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Target {
+    Prey,
+    Predator,
+    Carrion,
+}
+
+/// A question about the creature, answered by the `Agent`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Condition {
+    /// Its fullness is below this share of its maximum.
+    Hungry(f32),
+    /// One of these is within sight.
+    Sees(Target),
+    /// Its `UtilityAi` picked this behaviour.
+    Behaving(Behavior),
+}
+
+/// Something the creature does, carried out by the `Agent`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Action {
+    /// Steer towards the closest target.
+    Seek(Target),
+    /// Steer away from the closest target.
+    Flee(Target),
+    Wander,
+    /// Go for the closest carrion or prey, whichever is closer.
+    Eat,
+    /// Come to a halt.
+    Rest,
+}
+
+/// How a node fared this tick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Failure,
+    /// An action that is under way.
+    Running,
+}
+
+/// A node of a behaviour tree. The whole tree is ticked from the root every frame.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum BehaviorNode {
+    /// Ticks its children in order until one of them does not succeed.
+    Sequence(Vec<BehaviorNode>),
+    /// Ticks its children in order until one of them does not fail.
+    Selector(Vec<BehaviorNode>),
+    /// Succeeds if the condition holds, fails otherwise.
+    Condition(Condition),
+    Action(Action),
+}
+
+/// The creature a behaviour tree is ticked for.
+pub trait Agent {
+    fn check(&self, condition: &Condition) -> bool;
+    fn act(&mut self, action: &Action) -> Status;
+}
+
+impl BehaviorNode {
+    pub fn tick(&self, agent: &mut impl Agent) -> Status {
+        match self {
+            BehaviorNode::Sequence(children) => {
+                for child in children {
+                    match child.tick(agent) {
+                        Status::Success => continue,
+                        status => return status,
+                    }
+                }
+                Status::Success
+            }
+            BehaviorNode::Selector(children) => {
+                for child in children {
+                    match child.tick(agent) {
+                        Status::Failure => continue,
+                        status => return status,
+                    }
+                }
+                Status::Failure
+            }
+            BehaviorNode::Condition(condition) => {
+                if agent.check(condition) {
+                    Status::Success
+                } else {
+                    Status::Failure
+                }
+            }
+            BehaviorNode::Action(action) => agent.act(action),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestAgent {
+        hungry: bool,
+        actions: Vec<Action>,
+    }
+
+    impl Agent for TestAgent {
+        fn check(&self, condition: &Condition) -> bool {
+            match condition {
+                Condition::Hungry(_) => self.hungry,
+                _ => false,
+            }
+        }
+
+        fn act(&mut self, action: &Action) -> Status {
+            self.actions.push(action.clone());
+            Status::Running
+        }
+    }
+
+    #[test]
+    fn selector_runs_the_first_branch_that_does_not_fail() {
+        let tree = BehaviorNode::Selector(vec![
+            BehaviorNode::Sequence(vec![
+                BehaviorNode::Condition(Condition::Sees(Target::Predator)),
+                BehaviorNode::Action(Action::Flee(Target::Predator)),
+            ]),
+            BehaviorNode::Sequence(vec![
+                BehaviorNode::Condition(Condition::Hungry(0.5)),
+                BehaviorNode::Action(Action::Eat),
+            ]),
+            BehaviorNode::Action(Action::Wander),
+        ]);
+
+        let mut hungry = TestAgent {
+            hungry: true,
+            actions: Vec::new(),
+        };
+        assert_eq!(tree.tick(&mut hungry), Status::Running);
+        assert_eq!(hungry.actions, vec![Action::Eat]);
+
+        let mut full = TestAgent {
+            hungry: false,
+            actions: Vec::new(),
+        };
+        assert_eq!(tree.tick(&mut full), Status::Running);
+        assert_eq!(full.actions, vec![Action::Wander]);
+    }
+}
@@ -6,7 +6,7 @@ use amethyst::{
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Deserialize, Serialize, PrefabData)]
+#[derive(Clone, Debug, Deserialize, Serialize, PrefabData)]
 #[prefab(Component)]
 pub struct Circle {
     pub radius: f32,
@@ -2,14 +2,17 @@ use amethyst::{
     assets::{PrefabData, PrefabLoader, ProgressCounter, RonFormat},
     core::Named,
     derive::PrefabData,
-    ecs::{Component, DenseVecStorage, Entity, HashMapStorage, Read, Write, WriteStorage},
+    ecs::{
+        Component, DenseVecStorage, Entity, HashMapStorage, NullStorage, Read, Write, WriteStorage,
+    },
     prelude::*,
     Error,
 };
 //use amethyst_inspector::Inspect;
 use log::error;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use smart_default::SmartDefault;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize, PrefabData)]
@@ -23,17 +26,83 @@ impl Component for Health {
     type Storage = DenseVecStorage<Self>;
 }
 
-#[derive(Default, Debug, Clone, Deserialize, Serialize, PrefabData)]
+/// The kinds of damage an attack can deal. `Armor` resists each of them separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum DamageType {
+    Bite,
+    Trample,
+    Poison,
+}
+
+impl Default for DamageType {
+    fn default() -> Self {
+        DamageType::Bite
+    }
+}
+
+#[derive(SmartDefault, Debug, Clone, Deserialize, Serialize, PrefabData)]
 #[prefab(Component)]
+#[serde(default)]
 pub struct Damage {
     // Points subtracted from target's health per hit
     pub damage: f32,
+    pub damage_type: DamageType,
+    /// Chance for a hit to be critical, between 0 and 1.
+    pub critical_chance: f32,
+    /// Critical hits deal this many times the damage.
+    #[default(2.0)]
+    pub critical_multiplier: f32,
 }
 
 impl Component for Damage {
     type Storage = DenseVecStorage<Self>;
 }
 
+/// Protects a creature against attacks.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+pub struct Armor {
+    /// Share of the damage of each type that is absorbed, between 0 and 1. Damage types that
+    /// are not listed go through unhindered.
+    pub resistances: BTreeMap<DamageType, f32>,
+    /// Chance to dodge an attack from an attacker as fast as the creature, between 0 and 1.
+    pub evasion: f32,
+}
+
+impl Component for Armor {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Chance that an attack hits when nothing is faster than the other.
+const BASE_HIT_CHANCE: f32 = 0.9;
+/// Change of the hit chance per unit of top speed the attacker has over its defender.
+const HIT_CHANCE_PER_SPEED: f32 = 0.1;
+/// Even the slowest attacker hits now and then.
+const MIN_HIT_CHANCE: f32 = 0.1;
+
+/// Chance that an attack hits. Attackers that are faster than their defender hit more often,
+/// slower ones less often, and the evasion of the defender's armor lowers the chance further.
+pub fn hit_chance(attacker_speed: f32, defender_speed: f32, armor: Option<&Armor>) -> f32 {
+    let evasion = armor.map_or(0.0, |armor| armor.evasion);
+    let chance =
+        BASE_HIT_CHANCE + HIT_CHANCE_PER_SPEED * (attacker_speed - defender_speed) - evasion;
+    chance.max(MIN_HIT_CHANCE).min(1.0)
+}
+
+/// Damage a hit deals to a defender with the given armor.
+pub fn damage_dealt(damage: &Damage, armor: Option<&Armor>, critical: bool) -> f32 {
+    let resistance = armor
+        .and_then(|armor| armor.resistances.get(&damage.damage_type))
+        .map_or(0.0, |resistance| resistance.max(0.0).min(1.0));
+    let multiplier = if critical {
+        damage.critical_multiplier
+    } else {
+        1.0
+    };
+    damage.damage * multiplier * (1.0 - resistance)
+}
+
 ///
 ///
 ///
@@ -67,6 +136,40 @@ impl Cooldown {
     }
 }
 
+/// How close a creature has to be to attack, see `FindAttackSystem`. Distances are measured
+/// between the edges of the creatures' `Circle` colliders, so the reach does not grow with size.
+#[derive(SmartDefault, Debug, Clone, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+pub struct AttackRange {
+    /// Targets at most this far away are attacked.
+    pub reach: f32,
+    /// Targets at most this far away, but out of reach, are lunged at.
+    pub lunge_distance: f32,
+    /// While lunging the creature may move this many times as fast as its top speed.
+    #[default(2.0)]
+    pub lunge_speed: f32,
+    /// Seconds a lunge lasts.
+    #[default(0.3)]
+    pub lunge_duration: f32,
+}
+
+impl Component for AttackRange {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// A creature lunging at its prey. Until the lunge is over the `MovementSystem` lets it exceed
+/// its top speed by `speed_multiplier`.
+#[derive(Default, Debug, Clone, Deserialize, Serialize)]
+pub struct Lunge {
+    pub time_left: f32,
+    pub speed_multiplier: f32,
+}
+
+impl Component for Lunge {
+    type Storage = HashMapStorage<Self>;
+}
+
 /// Indicate whether the entity is part of a faction. Factions are used to represent groups of
 /// entities that attack each other, see `HasFaction`. A faction is an entity of its own and might
 /// specify properties using components.
@@ -109,67 +212,129 @@ impl<'a> PrefabData<'a> for HasFaction<String> {
     }
 }
 
-// Store the faction entities this component's owner considers to be prey
-/// The type is generic because we use `FactionPrey<Entity>` as a component and `FactionPrey<String>` for the prefab.
-#[derive(Default, Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
-pub struct FactionPrey<T> {
-    pub preys: Vec<T>,
+/// How the members of a faction treat the members of another faction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Relationship {
+    /// Hunted, and attacked on contact.
+    Prey,
+    /// Hunts us, so we flee from it.
+    Predator,
+    /// Never attacked.
+    Ally,
+    /// Ignored.
+    Neutral,
+    /// Competes for the same food. Attacked on contact, but not hunted.
+    Competitor,
+    /// Fled from, even though it does not hunt us.
+    Fear,
 }
 
-impl Component for FactionPrey<Entity> {
-    type Storage = HashMapStorage<Self>;
+impl Relationship {
+    /// The relationship the other side has with us, unless it declares one of its own.
+    fn mirrored(self) -> Relationship {
+        match self {
+            Relationship::Prey => Relationship::Predator,
+            Relationship::Predator => Relationship::Prey,
+            Relationship::Ally => Relationship::Ally,
+            Relationship::Competitor => Relationship::Competitor,
+            Relationship::Neutral | Relationship::Fear => Relationship::Neutral,
+        }
+    }
 }
 
-impl<T> FactionPrey<T> {
-    pub fn is_prey(&self, other: &T) -> bool
-    where
-        T: PartialEq,
-    {
-        self.preys.contains(other)
-    }
+/// The relationships a faction declares with other factions, by name. Factions can refer to each
+/// other in any order; the names are resolved into `FactionRelations` by the
+/// `FactionRelationsSystem` once the factions exist.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Serialize, Deserialize, PrefabData)]
+#[prefab(Component)]
+#[serde(transparent)]
+pub struct FactionRelationships(pub BTreeMap<String, Relationship>);
+
+impl Component for FactionRelationships {
+    type Storage = HashMapStorage<Self>;
 }
 
-impl<'a> PrefabData<'a> for FactionPrey<String> {
-    type SystemData = (Write<'a, Factions>, WriteStorage<'a, FactionPrey<Entity>>);
-    type Result = ();
+/// The relationship matrix between all factions, by faction entity.
+#[derive(Default)]
+pub struct FactionRelations {
+    relations: HashMap<(Entity, Entity), Relationship>,
+    /// Number of factions that existed when the matrix was resolved.
+    resolved_factions: usize,
+}
 
-    fn add_to_entity(
-        &self,
-        entity: Entity,
-        system_data: &mut Self::SystemData,
-        _entities: &[Entity],
-        _: &[Entity],
-    ) -> Result<Self::Result, Error> {
-        let ref factions = (system_data.0).0;
-        let preys: Vec<Entity> = self
-            .preys
-            .iter()
-            .map(|prey| {
-                let faction = factions.get(prey);
-                if faction.is_none() {
-                    error!("Failed to load faction {:?}", prey);
+impl FactionRelations {
+    /// Resolves the declared relationships of every faction. Relationships with factions that do
+    /// not exist (yet) are left out.
+    pub fn resolve<'a>(
+        factions: &Factions,
+        declarations: impl Iterator<Item = (Entity, &'a FactionRelationships)>,
+    ) -> FactionRelations {
+        let mut relations = HashMap::new();
+        for (faction, declaration) in declarations {
+            for (name, relationship) in &declaration.0 {
+                if let Some(other) = factions.get(name) {
+                    relations.insert((faction, other), *relationship);
                 }
-                faction
+            }
+        }
+        FactionRelations {
+            relations,
+            resolved_factions: factions.len(),
+        }
+    }
+
+    pub fn resolved_factions(&self) -> usize {
+        self.resolved_factions
+    }
+
+    /// How `faction` treats `other`. When `faction` declares nothing about `other`, it is derived
+    /// from what `other` declares about `faction`: whoever is hunted by a faction considers it a
+    /// predator, and the other way around. Otherwise they are neutral.
+    pub fn get(&self, faction: Entity, other: Entity) -> Relationship {
+        if let Some(relationship) = self.relations.get(&(faction, other)) {
+            return *relationship;
+        }
+        self.relations
+            .get(&(other, faction))
+            .map_or(Relationship::Neutral, |relationship| {
+                relationship.mirrored()
             })
-            .filter(|faction| faction.is_some())
-            .map(|faction| *faction.unwrap())
-            .collect();
-        system_data
-            .1
-            .insert(entity, FactionPrey { preys })
-            .expect("unreachable: we are inserting");
-        Ok(())
     }
+
+    /// Whether members of `faction` attack members of `other` on contact.
+    pub fn attacks(&self, faction: Entity, other: Entity) -> bool {
+        match self.get(faction, other) {
+            Relationship::Prey | Relationship::Competitor => true,
+            _ => false,
+        }
+    }
+
+    /// Whether members of `faction` flee from members of `other`.
+    pub fn flees(&self, faction: Entity, other: Entity) -> bool {
+        match self.get(faction, other) {
+            Relationship::Predator | Relationship::Fear => true,
+            _ => false,
+        }
+    }
+}
+
+/// Marks a faction whose members are remains rather than living creatures, like carcasses. They
+/// are eaten as carrion, but never count as prey or predators, see `QueryPredatorsAndPreySystem`.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+pub struct CarrionTag;
+
+impl Component for CarrionTag {
+    type Storage = NullStorage<Self>;
 }
 
 // Prefab data for the factions. The prefab will populate the faction lookup table.
 impl<'a> PrefabData<'a> for FactionPrefabData {
     type SystemData = (
         <Named as PrefabData<'a>>::SystemData,
-        <FactionPrey<String> as PrefabData<'a>>::SystemData,
-        // We can't access Factions here, because Factions is already in use by `FactionPrey<String>::SystemData`.
-        // As a workaround we use `Write` in `FactionPrey<String>::SystemData.0` instead of `Read`
-        // Write<'a, Factions>,
+        Write<'a, Factions>,
+        <FactionRelationships as PrefabData<'a>>::SystemData,
+        <CarrionTag as PrefabData<'a>>::SystemData,
     );
     type Result = ();
 
@@ -180,17 +345,21 @@ impl<'a> PrefabData<'a> for FactionPrefabData {
         entities: &[Entity],
         children: &[Entity],
     ) -> Result<Self::Result, Error> {
-        let (ref mut named, ref mut faction_preys) = system_data;
+        let (ref mut named, ref mut factions, ref mut relationships, ref mut carrion_tags) =
+            system_data;
 
         // Update our faction lookup table
         if let Some(ref name) = self.name {
-            (faction_preys.0).0.insert(name.name.to_string(), entity);
+            factions.0.insert(name.name.to_string(), entity);
         }
         self.name
             .add_to_entity(entity, named, entities, children)
             .expect("unreachable");
-        self.faction_preys
-            .add_to_entity(entity, faction_preys, entities, children)
+        self.relationships
+            .add_to_entity(entity, relationships, entities, children)
+            .expect("unreachable");
+        self.carrion_tag
+            .add_to_entity(entity, carrion_tags, entities, children)
             .expect("unreachable");
         Ok(())
     }
@@ -201,15 +370,27 @@ impl<'a> PrefabData<'a> for FactionPrefabData {
 #[serde(deny_unknown_fields)]
 pub struct FactionPrefabData {
     name: Option<Named>,
-    faction_preys: Option<FactionPrey<String>>,
+    relationships: Option<FactionRelationships>,
+    carrion_tag: Option<CarrionTag>,
 }
 
 #[derive(Default)]
 pub struct Factions(HashMap<String, Entity>);
 
-// The factions are stored inside the Ron file in a sorted way. They can only define
-// factions as prey that are on top of their definition. For example, 'Plants' cannot define 'Herbivores' as their prey
-// because 'Herbivores' is defined after 'Plants'.
+impl Factions {
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.0.get(name).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
 pub fn load_factions(world: &mut World) {
     let prefab_handle = world.exec(|loader: PrefabLoader<'_, FactionPrefabData>| {
         loader.load("prefabs/factions.ron", RonFormat, ())
@@ -225,5 +406,77 @@ pub struct CombatPrefabData {
     health: Option<Health>,
     speed: Option<Speed>,
     damage: Option<Damage>,
+    armor: Option<Armor>,
+    attack_range: Option<AttackRange>,
     has_faction: Option<HasFaction<String>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::ecs::{Builder, World, WorldExt};
+
+    #[test]
+    fn armor_resists_damage_by_type() {
+        let mut armor = Armor::default();
+        armor.resistances.insert(DamageType::Bite, 0.25);
+        let bite = Damage {
+            damage: 20.0,
+            ..Default::default()
+        };
+        let trample = Damage {
+            damage: 20.0,
+            damage_type: DamageType::Trample,
+            ..Default::default()
+        };
+
+        assert_eq!(damage_dealt(&bite, Some(&armor), false), 15.0);
+        assert_eq!(damage_dealt(&bite, Some(&armor), true), 30.0);
+        assert_eq!(damage_dealt(&trample, Some(&armor), false), 20.0);
+        assert_eq!(damage_dealt(&trample, None, false), 20.0);
+    }
+
+    #[test]
+    fn faster_attackers_hit_more_often() {
+        let armor = Armor {
+            evasion: 0.2,
+            ..Default::default()
+        };
+        assert!(hit_chance(3.0, 1.0, None) > hit_chance(1.0, 1.0, None));
+        assert!(hit_chance(1.0, 1.0, Some(&armor)) < hit_chance(1.0, 1.0, None));
+        assert_eq!(hit_chance(10.0, 0.0, None), 1.0);
+        assert_eq!(hit_chance(0.0, 10.0, Some(&armor)), MIN_HIT_CHANCE);
+    }
+
+    #[test]
+    fn undeclared_relationships_are_derived_from_the_other_side() {
+        let mut world = World::new();
+        let mut factions = Factions::default();
+        for name in &["Plants", "Herbivores", "Carnivores"] {
+            factions
+                .0
+                .insert(name.to_string(), world.create_entity().build());
+        }
+        let plants = factions.get("Plants").unwrap();
+        let herbivores = factions.get("Herbivores").unwrap();
+        let carnivores = factions.get("Carnivores").unwrap();
+
+        let mut declared = FactionRelationships::default();
+        declared.0.insert("Plants".to_string(), Relationship::Prey);
+        declared
+            .0
+            .insert("Carnivores".to_string(), Relationship::Fear);
+        // Unknown factions are ignored.
+        declared
+            .0
+            .insert("Fungi".to_string(), Relationship::Competitor);
+        let relations =
+            FactionRelations::resolve(&factions, vec![(herbivores, &declared)].into_iter());
+
+        assert!(relations.attacks(herbivores, plants));
+        assert_eq!(relations.get(plants, herbivores), Relationship::Predator);
+        assert!(relations.flees(herbivores, carnivores));
+        assert_eq!(relations.get(carnivores, herbivores), Relationship::Neutral);
+        assert_eq!(relations.resolved_factions(), 3);
+    }
+}
@@ -1,9 +1,13 @@
+#[cfg(feature = "render")]
 use amethyst::{
-    assets::{AssetPrefab, PrefabData, ProgressCounter},
+    assets::AssetPrefab,
+    gltf::{GltfSceneAsset, GltfSceneFormat},
+};
+use amethyst::{
+    assets::{PrefabData, ProgressCounter},
     core::{math::Vector3, Named},
     derive::PrefabData,
     ecs::{Component, DenseVecStorage, Entity, NullStorage, WriteStorage},
-    gltf::{GltfSceneAsset, GltfSceneFormat},
     Error,
 };
 //use amethyst_inspector::Inspect;
@@ -11,8 +15,22 @@ use amethyst::{
 use serde::{Deserialize, Serialize};
 
 use crate::components::{
-    collider::Circle, combat::CombatPrefabData, digestion::DigestionPrefabData,
-    perception::Perception,
+    aging::Age,
+    behavior_tree::BehaviorTree,
+    collider::Circle,
+    combat::CombatPrefabData,
+    decay::Decay,
+    digestion::DigestionPrefabData,
+    genetics::Genetics,
+    memory::Memory,
+    perception::{Occluder, Perception},
+    photosynthesis::Photosynthesis,
+    regeneration::Regeneration,
+    reproduction::Reproduction,
+    scavenger::Scavenger,
+    seeds::SeedDispersal,
+    steering::Steering,
+    utility::UtilityAi,
 };
 
 pub type CreatureType = String;
@@ -125,6 +143,30 @@ impl Component for Carcass {
     type Storage = DenseVecStorage<Self>;
 }
 
+/// Stands in for the gltf scene of a creature when the game is built without the `render` feature.
+/// It accepts the asset declared in the prefab and adds nothing to the entity.
+#[cfg(not(feature = "render"))]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum RenderAsset {
+    File(String, ()),
+}
+
+#[cfg(not(feature = "render"))]
+impl<'a> PrefabData<'a> for RenderAsset {
+    type SystemData = ();
+    type Result = ();
+
+    fn add_to_entity(
+        &self,
+        _entity: Entity,
+        _system_data: &mut Self::SystemData,
+        _entities: &[Entity],
+        _children: &[Entity],
+    ) -> Result<Self::Result, Error> {
+        Ok(())
+    }
+}
+
 // This is the main prefab data for creatures.
 // It defines all the components that a creature could have.
 // In the prefab, it is not necessary to define all of them (due to Option).
@@ -135,7 +177,10 @@ impl Component for Carcass {
 pub struct CreaturePrefabData {
     pub name: Option<Named>,
     creature_tag: Option<CreatureTag>,
+    #[cfg(feature = "render")]
     gltf: Option<AssetPrefab<GltfSceneAsset, GltfSceneFormat>>,
+    #[cfg(not(feature = "render"))]
+    gltf: Option<RenderAsset>,
     movement: Option<Movement>,
     wander: Option<Wander>,
     collider: Option<Circle>,
@@ -143,10 +188,31 @@ pub struct CreaturePrefabData {
     combat: Option<CombatPrefabData>,
     intelligence_tag: Option<IntelligenceTag>,
     perception: Option<Perception>,
+    occluder: Option<Occluder>,
+    memory: Option<Memory>,
     ricochet_tag: Option<RicochetTag>,
     carcass: Option<Carcass>,
     avoid_obstacles_tag: Option<AvoidObstaclesTag>,
     despawn_when_out_of_bounds_tag: Option<DespawnWhenOutOfBoundsTag>,
     topplegrass_tag: Option<TopplegrassTag>,
     falling_tag: Option<FallingTag>,
+    genetics: Option<Genetics>,
+    reproduction: Option<Reproduction>,
+    photosynthesis: Option<Photosynthesis>,
+    seed_dispersal: Option<SeedDispersal>,
+    age: Option<Age>,
+    decay: Option<Decay>,
+    scavenger: Option<Scavenger>,
+    regeneration: Option<Regeneration>,
+    utility: Option<UtilityAi>,
+    behavior_tree: Option<BehaviorTree>,
+    steering: Option<Steering>,
+}
+
+impl CreaturePrefabData {
+    /// Drop every asset that only matters for rendering, so the prefab can be instantiated
+    /// without a renderer or a gltf loader.
+    pub fn strip_render_assets(&mut self) {
+        self.gltf = None;
+    }
 }
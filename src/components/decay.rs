@@ -0,0 +1,41 @@
+use amethyst::{
+    assets::PrefabData,
+    core::math::Vector3,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// Makes a carcass rot away. Its `Nutrition` seeps into the soil, see `SoilFertility`, and the
+/// carcass shrinks with the nutrition it has left until nothing remains.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+pub struct Decay {
+    /// Points of nutrition that rot into the soil every second.
+    pub rot_rate: f32,
+    /// Share of its original size the carcass keeps right before it is gone.
+    pub min_scale: f32,
+    /// Nutrition and scale of the fresh carcass, taken when the `DecaySystem` first sees it.
+    pub initial_nutrition: Option<f32>,
+    pub initial_scale: Option<Vector3<f32>>,
+}
+
+impl Component for Decay {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Decay {
+    /// Scale of the carcass with `nutrition` points of nutrition left.
+    pub fn scale(&self, nutrition: f32) -> Option<Vector3<f32>> {
+        let initial_scale = self.initial_scale?;
+        let remaining = match self.initial_nutrition {
+            Some(initial_nutrition) if initial_nutrition > 0.0 => {
+                (nutrition / initial_nutrition).max(0.0).min(1.0)
+            }
+            _ => 1.0,
+        };
+        Some(initial_scale * (self.min_scale + (1.0 - self.min_scale) * remaining))
+    }
+}
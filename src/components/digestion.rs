@@ -44,7 +44,7 @@ impl Component for Nutrition {
 #[serde(default)]
 #[serde(deny_unknown_fields)]
 pub struct DigestionPrefabData {
-    fullness: Fullness,
-    digestion: Digestion,
+    fullness: Option<Fullness>,
+    digestion: Option<Digestion>,
     nutrition: Option<Nutrition>,
 }
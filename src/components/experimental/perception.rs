@@ -1,23 +1,84 @@
 use amethyst::{
     assets::PrefabData,
+    core::math::Vector2,
     derive::PrefabData,
     ecs::{BitSet, Component, DenseVecStorage, Entity, WriteStorage},
     Error,
 };
 //use amethyst_inspector::Inspect;
 use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
 
-#[derive(Default, Clone, Debug, Serialize, Deserialize, PrefabData)]
+#[derive(SmartDefault, Clone, Debug, Serialize, Deserialize, PrefabData)]
 #[prefab(Component)]
 #[serde(default)]
 pub struct Perception {
     pub range: f32,
+    /// Angle of the view cone in degrees, centered on the heading of the creature. 360 sees all
+    /// around.
+    #[default(360.0)]
+    pub field_of_view: f32,
+    /// Anything this close is noticed, whichever way the creature is facing and whatever is in
+    /// between.
+    pub rear_range: f32,
 }
 
 impl Component for Perception {
     type Storage = DenseVecStorage<Self>;
 }
 
+impl Perception {
+    /// Whether something at `offset` from a creature heading in `heading` is in range and within
+    /// its view cone. A creature without a heading looks all around.
+    pub fn in_view(&self, offset: &Vector2<f32>, heading: &Vector2<f32>) -> bool {
+        let distance = offset.norm();
+        if distance > self.range {
+            return false;
+        }
+        if distance <= self.rear_range
+            || self.field_of_view >= 360.0
+            || distance < std::f32::EPSILON
+            || heading.norm() < std::f32::EPSILON
+        {
+            return true;
+        }
+        heading.angle(offset) <= (self.field_of_view / 2.0).to_radians()
+    }
+
+    /// Whether something at `offset` is close enough to be noticed without looking.
+    pub fn senses(&self, offset: &Vector2<f32>) -> bool {
+        offset.norm() <= self.rear_range.min(self.range)
+    }
+}
+
+/// Blocks the line of sight of creatures, like a tree or a rock.
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+pub struct Occluder {
+    pub radius: f32,
+}
+
+impl Component for Occluder {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Occluder {
+    /// Whether this occluder, at `offset` from the observer, blocks its view of something at
+    /// `target_offset`. Only occluders between the two block the view.
+    pub fn blocks(&self, offset: &Vector2<f32>, target_offset: &Vector2<f32>) -> bool {
+        let length_squared = target_offset.norm_squared();
+        if length_squared < std::f32::EPSILON {
+            return false;
+        }
+        let along = offset.dot(target_offset) / length_squared;
+        if along <= 0.0 || along >= 1.0 {
+            return false;
+        }
+        (offset - target_offset * along).norm() < self.radius
+    }
+}
+
 #[derive(Default, Clone, Debug)]
 pub struct DetectedEntities {
     pub entities: BitSet,
@@ -26,3 +87,32 @@ pub struct DetectedEntities {
 impl Component for DetectedEntities {
     type Storage = DenseVecStorage<Self>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creatures_see_ahead_and_sense_behind() {
+        let perception = Perception {
+            range: 5.0,
+            field_of_view: 90.0,
+            rear_range: 1.0,
+        };
+        let heading = Vector2::new(1.0, 0.0);
+        assert!(perception.in_view(&Vector2::new(3.0, 1.0), &heading));
+        assert!(!perception.in_view(&Vector2::new(1.0, 3.0), &heading));
+        assert!(!perception.in_view(&Vector2::new(-3.0, 0.0), &heading));
+        assert!(perception.in_view(&Vector2::new(-0.5, 0.0), &heading));
+        assert!(!perception.in_view(&Vector2::new(6.0, 0.0), &heading));
+    }
+
+    #[test]
+    fn occluders_block_what_is_behind_them() {
+        let tree = Occluder { radius: 0.5 };
+        let target = Vector2::new(4.0, 0.0);
+        assert!(tree.blocks(&Vector2::new(2.0, 0.3), &target));
+        assert!(!tree.blocks(&Vector2::new(2.0, 1.0), &target));
+        assert!(!tree.blocks(&Vector2::new(5.0, 0.0), &target));
+    }
+}
@@ -0,0 +1,126 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, HashMapStorage, WriteStorage},
+    Error,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A heritable trait. Every gene controls one field of a component of the creature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum Gene {
+    /// `Movement::max_movement_speed`
+    MaxMovementSpeed,
+    /// `Perception::range`
+    PerceptionRange,
+    /// `Circle::radius`
+    ColliderRadius,
+    /// `Damage::damage`
+    Damage,
+    /// `Digestion::nutrition_burn_rate`
+    NutritionBurnRate,
+    /// `Health::max_health`
+    MaxHealth,
+}
+
+/// How a single gene may vary between generations.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GeneBounds {
+    pub min: f32,
+    pub max: f32,
+    /// Chance for the gene to mutate when it is passed on, between 0 and 1.
+    pub mutation_rate: f32,
+    /// Largest change a mutation makes, relative to the value of the parent.
+    pub mutation_strength: f32,
+}
+
+/// Declares which genes a creature type has and how they mutate. Genes that are not listed
+/// never change from the value in the prefab.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+pub struct Genetics {
+    pub genes: BTreeMap<Gene, GeneBounds>,
+}
+
+impl Component for Genetics {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Genetics {
+    /// The genome of an offspring of `parent`. Every gene has a chance to mutate and always stays
+    /// within its bounds.
+    pub fn inherit<R: Rng>(&self, parent: &Genome, rng: &mut R) -> Genome {
+        let genes = self
+            .genes
+            .iter()
+            .filter_map(|(gene, bounds)| {
+                let mut value = parent.get(*gene)?;
+                if rng.gen::<f32>() < bounds.mutation_rate {
+                    value += value * bounds.mutation_strength * rng.gen_range(-1.0, 1.0);
+                }
+                Some((*gene, value.max(bounds.min).min(bounds.max)))
+            })
+            .collect();
+        Genome { genes }
+    }
+}
+
+/// The actual gene values of a creature. Set once, after the prefab of the creature has been
+/// instantiated, by the `GenomeSystem`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Genome {
+    pub genes: BTreeMap<Gene, f32>,
+}
+
+impl Component for Genome {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Genome {
+    pub fn get(&self, gene: Gene) -> Option<f32> {
+        self.genes.get(&gene).cloned()
+    }
+}
+
+/// The genome of the parent of a newborn creature, waiting for the `GenomeSystem` to pass it on.
+pub struct InheritedGenome(pub Genome);
+
+impl Component for InheritedGenome {
+    type Storage = HashMapStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg32;
+
+    #[test]
+    fn inherited_genes_stay_within_bounds() {
+        let mut genetics = Genetics::default();
+        genetics.genes.insert(
+            Gene::MaxMovementSpeed,
+            GeneBounds {
+                min: 1.0,
+                max: 2.0,
+                mutation_rate: 1.0,
+                mutation_strength: 0.5,
+            },
+        );
+        let mut genome = Genome::default();
+        genome.genes.insert(Gene::MaxMovementSpeed, 1.5);
+        genome.genes.insert(Gene::Damage, 10.0);
+
+        let mut rng = Pcg32::seed_from_u64(7);
+        for _ in 0..100 {
+            genome = genetics.inherit(&genome, &mut rng);
+            let speed = genome.get(Gene::MaxMovementSpeed).unwrap();
+            assert!(speed >= 1.0 && speed <= 2.0);
+        }
+        // Genes without bounds are not part of the creature's genetics.
+        assert_eq!(genome.get(Gene::Damage), None);
+    }
+}
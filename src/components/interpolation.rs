@@ -0,0 +1,25 @@
+use amethyst::{
+    core::math::Vector3,
+    ecs::{Component, DenseVecStorage},
+};
+
+/// The translation of an entity at the last two simulation ticks. Rendering blends between the
+/// two, so movement looks smooth even though the simulation only advances in fixed ticks.
+#[derive(Clone, Debug)]
+pub struct InterpolatedTranslation {
+    pub previous: Vector3<f32>,
+    pub current: Vector3<f32>,
+}
+
+impl InterpolatedTranslation {
+    pub fn new(translation: Vector3<f32>) -> InterpolatedTranslation {
+        InterpolatedTranslation {
+            previous: translation,
+            current: translation,
+        }
+    }
+}
+
+impl Component for InterpolatedTranslation {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,18 @@
+use amethyst::ecs::{Component, DenseVecStorage, HashMapStorage};
+use serde::{Deserialize, Serialize};
+
+/// The id of a creature in the `LineageRegistry`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LineageId(pub u64);
+
+impl Component for LineageId {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// The lineage id of the parent of a newborn creature, until the newborn is registered in the
+/// `LineageRegistry`.
+pub struct LineageParent(pub u64);
+
+impl Component for LineageParent {
+    type Storage = HashMapStorage<Self>;
+}
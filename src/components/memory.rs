@@ -0,0 +1,160 @@
+use amethyst::{
+    assets::PrefabData,
+    core::math::Vector3,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+/// The things a creature can remember the location of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum MemoryKind {
+    Prey,
+    Predator,
+    Carrion,
+    /// Where the creature was born. Home is never forgotten.
+    Home,
+}
+
+/// A location the creature remembers.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Remembered {
+    pub kind: MemoryKind,
+    pub position: Vector3<f32>,
+    /// `SimulationTime::elapsed_seconds` when the creature last saw it there.
+    pub seen_at: f64,
+}
+
+/// Lets a creature remember where it last saw prey, predators and carrion, see `MemorySystem`.
+/// While it perceives none of them, it acts on what it remembers instead.
+#[derive(Clone, Debug, SmartDefault, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+pub struct Memory {
+    /// Seconds after which a memory is forgotten. Memories fade over this time.
+    #[default(20.0)]
+    pub duration: f32,
+    /// Sightings this close to a memory of the same kind refresh it rather than adding another.
+    #[default(1.0)]
+    pub merge_radius: f32,
+    /// Most memories the creature keeps of each kind. The oldest ones go first.
+    #[default(4)]
+    pub capacity: usize,
+    pub memories: Vec<Remembered>,
+}
+
+impl Component for Memory {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Memory {
+    /// Remembers seeing something of `kind` at `position`.
+    pub fn remember(&mut self, kind: MemoryKind, position: Vector3<f32>, now: f64) {
+        let merge_radius = self.merge_radius;
+        match self.memories.iter_mut().find(|memory| {
+            memory.kind == kind && (memory.position - position).norm() < merge_radius
+        }) {
+            Some(memory) => {
+                memory.position = position;
+                memory.seen_at = now;
+            }
+            None => self.memories.push(Remembered {
+                kind,
+                position,
+                seen_at: now,
+            }),
+        }
+
+        let count = self.memories.iter().filter(|m| m.kind == kind).count();
+        if count > self.capacity {
+            let oldest = self
+                .memories
+                .iter()
+                .enumerate()
+                .filter(|(_, memory)| memory.kind == kind)
+                .min_by(|(_, a), (_, b)| {
+                    a.seen_at
+                        .partial_cmp(&b.seen_at)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .map(|(index, _)| index);
+            if let Some(index) = oldest {
+                self.memories.remove(index);
+            }
+        }
+    }
+
+    /// Forgets every memory of `kind` within `radius` of `position`.
+    pub fn forget_around(&mut self, kind: MemoryKind, position: &Vector3<f32>, radius: f32) {
+        self.memories
+            .retain(|memory| memory.kind != kind || (memory.position - position).norm() >= radius);
+    }
+
+    /// Forgets the memories that have faded completely.
+    pub fn fade(&mut self, now: f64) {
+        let duration = f64::from(self.duration);
+        self.memories
+            .retain(|memory| memory.kind == MemoryKind::Home || now - memory.seen_at < duration);
+    }
+
+    /// How clearly the creature remembers, from 1 for what it just saw down to 0 for what it
+    /// is about to forget.
+    pub fn strength(&self, memory: &Remembered, now: f64) -> f32 {
+        if memory.kind == MemoryKind::Home {
+            return 1.0;
+        }
+        if self.duration <= 0.0 {
+            return 0.0;
+        }
+        (1.0 - (now - memory.seen_at) as f32 / self.duration)
+            .max(0.0)
+            .min(1.0)
+    }
+
+    /// The remembered location of `kind` closest to `position`.
+    pub fn recall(&self, kind: MemoryKind, position: &Vector3<f32>) -> Option<&Remembered> {
+        self.memories
+            .iter()
+            .filter(|memory| memory.kind == kind)
+            .min_by(|a, b| {
+                (a.position - position)
+                    .norm_squared()
+                    .partial_cmp(&(b.position - position).norm_squared())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memories_are_refreshed_and_fade() {
+        let mut memory = Memory {
+            duration: 10.0,
+            merge_radius: 1.0,
+            capacity: 2,
+            memories: Vec::new(),
+        };
+        memory.remember(MemoryKind::Predator, Vector3::new(0.0, 0.0, 0.0), 0.0);
+        memory.remember(MemoryKind::Predator, Vector3::new(0.5, 0.0, 0.0), 4.0);
+        memory.remember(MemoryKind::Prey, Vector3::new(5.0, 0.0, 0.0), 6.0);
+        assert_eq!(memory.memories.len(), 2);
+
+        let predator = memory
+            .recall(MemoryKind::Predator, &Vector3::zeros())
+            .cloned()
+            .unwrap();
+        assert_eq!(predator.position, Vector3::new(0.5, 0.0, 0.0));
+        assert_eq!(memory.strength(&predator, 9.0), 0.5);
+
+        memory.fade(15.0);
+        assert!(memory
+            .recall(MemoryKind::Predator, &Vector3::zeros())
+            .is_none());
+        assert!(memory.recall(MemoryKind::Prey, &Vector3::zeros()).is_some());
+    }
+}
@@ -1,8 +1,22 @@
+pub mod aging;
+pub mod behavior_tree;
 pub mod collider;
 pub mod combat;
 pub mod creatures;
+pub mod decay;
 pub mod digestion;
+pub mod genetics;
+pub mod interpolation;
+pub mod lineage;
+pub mod memory;
+pub mod photosynthesis;
+pub mod regeneration;
+pub mod reproduction;
+pub mod scavenger;
+pub mod seeds;
+pub mod steering;
 pub mod swarm;
+pub mod utility;
 
 mod experimental;
 pub use experimental::*;
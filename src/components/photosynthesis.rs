@@ -0,0 +1,45 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// Lets a plant live off sunlight instead of food. Light fills up its `Fullness`, and the stored
+/// energy regrows the `Nutrition` and `Health` that grazers took away. The plant grows with its
+/// `Nutrition`.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(deny_unknown_fields)]
+pub struct Photosynthesis {
+    /// Points of fullness gained every second in full light.
+    pub efficiency: f32,
+    /// Points of fullness lost every second, day and night.
+    pub upkeep: f32,
+    /// Points of nutrition and of health regrown every second. Every point costs a point of
+    /// fullness.
+    pub regrowth_rate: f32,
+    /// Nutrition of a fully grown plant.
+    pub max_nutrition: f32,
+    /// Scale of the plant without any nutrition left.
+    pub min_scale: f32,
+    /// Scale of a fully grown plant.
+    pub max_scale: f32,
+}
+
+impl Component for Photosynthesis {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Photosynthesis {
+    /// Scale of a plant with `nutrition` points of nutrition.
+    pub fn scale(&self, nutrition: f32) -> f32 {
+        let growth = if self.max_nutrition > 0.0 {
+            (nutrition / self.max_nutrition).max(0.0).min(1.0)
+        } else {
+            1.0
+        };
+        self.min_scale + (self.max_scale - self.min_scale) * growth
+    }
+}
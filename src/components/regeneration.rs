@@ -0,0 +1,84 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+use crate::components::{combat::Health, digestion::Fullness};
+
+/// Lets a wounded creature heal while it is well fed. Healing uses up fullness, see
+/// `RegenerationSystem`.
+#[derive(Clone, Debug, SmartDefault, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct Regeneration {
+    /// Points of health healed every second.
+    pub rate: f32,
+    /// Share of its maximum fullness above which the creature heals.
+    #[default(0.5)]
+    pub min_fullness: f32,
+    /// Points of fullness it costs to heal one point of health.
+    #[default(1.0)]
+    pub fullness_per_health: f32,
+}
+
+impl Component for Regeneration {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Regeneration {
+    /// Points of health the creature heals in `delta_seconds`. It never heals above its maximum
+    /// health, nor spends the fullness it needs to stay above `min_fullness`.
+    pub fn healed(&self, health: &Health, fullness: &Fullness, delta_seconds: f32) -> f32 {
+        let spare_fullness = fullness.value - self.min_fullness * fullness.max;
+        let affordable = if self.fullness_per_health > 0.0 {
+            spare_fullness / self.fullness_per_health
+        } else {
+            std::f32::INFINITY
+        };
+        (self.rate * delta_seconds)
+            .min(health.max_health - health.value)
+            .min(affordable)
+            .max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn healing_is_limited_by_wounds_and_spare_fullness() {
+        let regeneration = Regeneration {
+            rate: 10.0,
+            min_fullness: 0.5,
+            fullness_per_health: 2.0,
+        };
+        let health = |value| Health {
+            max_health: 100.0,
+            value,
+        };
+        let fullness = |value| Fullness { max: 100.0, value };
+
+        assert_eq!(
+            regeneration.healed(&health(50.0), &fullness(100.0), 0.5),
+            5.0
+        );
+        assert_eq!(
+            regeneration.healed(&health(98.0), &fullness(100.0), 0.5),
+            2.0
+        );
+        assert_eq!(
+            regeneration.healed(&health(50.0), &fullness(54.0), 0.5),
+            2.0
+        );
+        assert_eq!(
+            regeneration.healed(&health(50.0), &fullness(40.0), 0.5),
+            0.0
+        );
+    }
+}
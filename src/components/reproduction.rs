@@ -0,0 +1,28 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// Lets a creature have offspring of its own type once it has eaten enough.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+pub struct Reproduction {
+    /// Fullness above which the creature reproduces.
+    pub fullness_threshold: f32,
+    /// Fullness it costs the parent to reproduce, however many offspring it has.
+    pub energy_cost: f32,
+    /// Seconds before the creature can reproduce again.
+    pub cooldown: f32,
+    /// Number of offspring born at once.
+    pub litter_size: u32,
+    /// Seconds left until the creature can reproduce again.
+    pub time_left: f32,
+}
+
+impl Component for Reproduction {
+    type Storage = DenseVecStorage<Self>;
+}
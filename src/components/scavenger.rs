@@ -0,0 +1,21 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// Lets a creature seek out carrion, the members of factions with a `CarrionTag` that its own
+/// faction preys on. A hungry scavenger goes for carrion before live prey.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(deny_unknown_fields)]
+pub struct Scavenger {
+    /// Share of its maximum fullness below which the creature prefers carrion.
+    pub hunger_threshold: f32,
+}
+
+impl Component for Scavenger {
+    type Storage = DenseVecStorage<Self>;
+}
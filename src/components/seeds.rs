@@ -0,0 +1,80 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// Lets a plant release seeds that drift along the wind and grow into new plants where they land,
+/// see `SeedDispersalSystem` and `GerminationSystem`.
+#[derive(Default, Debug, Clone, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(deny_unknown_fields)]
+pub struct SeedDispersal {
+    /// Seconds between two releases.
+    pub interval: f32,
+    /// Seconds until the next release.
+    pub time_left: f32,
+    pub seeds_per_release: u32,
+    /// Fullness the plant needs before it releases any seeds.
+    pub min_fullness: f32,
+    /// Fullness every seed costs the plant.
+    pub energy_cost: f32,
+    /// Upward speed of a released seed. The higher it flies, the further the wind carries it.
+    pub launch_speed: f32,
+    /// Chance for a seed that lands away from other plants to germinate, between 0 and 1.
+    pub germination_chance: f32,
+    /// Radius around a landed seed in which other plants compete with it.
+    pub crowding_radius: f32,
+    /// Number of plants within `crowding_radius` at which seeds no longer germinate.
+    pub crowding_limit: u32,
+}
+
+impl Component for SeedDispersal {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// An airborne seed, drifting with the wind until it lands.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Seed {
+    /// Creature type of the plant the seed grows into.
+    pub creature_type: String,
+    pub germination_chance: f32,
+    pub crowding_radius: f32,
+    pub crowding_limit: u32,
+}
+
+impl Component for Seed {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl Seed {
+    /// Chance to germinate next to `neighbours` other plants. Every neighbour lowers the chance,
+    /// until it reaches zero at the crowding limit.
+    pub fn germination_chance(&self, neighbours: u32) -> f32 {
+        if neighbours >= self.crowding_limit {
+            return 0.0;
+        }
+        self.germination_chance * (1.0 - neighbours as f32 / self.crowding_limit as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crowding_lowers_germination_chance() {
+        let seed = Seed {
+            creature_type: "Plant".to_string(),
+            germination_chance: 0.8,
+            crowding_radius: 1.0,
+            crowding_limit: 4,
+        };
+        assert_eq!(seed.germination_chance(0), 0.8);
+        assert_eq!(seed.germination_chance(2), 0.4);
+        assert_eq!(seed.germination_chance(4), 0.0);
+        assert_eq!(seed.germination_chance(10), 0.0);
+    }
+}
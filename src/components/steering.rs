@@ -0,0 +1,48 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+/// Replaces plain seeking with a blend of steering behaviours, see `SteeringSystem`. Every force
+/// is multiplied by its weight before they are added up; a weight of zero turns the behaviour off.
+#[derive(Clone, Debug, SmartDefault, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct Steering {
+    /// Chase prey where it is going to be rather than where it is.
+    #[default(1.0)]
+    pub pursue: f32,
+    /// Run from where predators are going to be.
+    #[default(1.0)]
+    pub evade: f32,
+    /// Approach carrion, slowing down on the way in.
+    #[default(1.0)]
+    pub arrive: f32,
+    /// Keep some room between the creature and the members of its faction.
+    pub separation: f32,
+    /// Head the same way as the members of its faction.
+    pub alignment: f32,
+    /// Stay close to the members of its faction.
+    pub cohesion: f32,
+    /// Pursuers and evaders look at most this many seconds ahead.
+    #[default(1.0)]
+    pub prediction_time: f32,
+    /// Arriving creatures start to slow down this close to their target.
+    #[default(1.0)]
+    pub slowdown_radius: f32,
+    /// Members of its faction this close form the creature's flock.
+    #[default(2.0)]
+    pub flock_radius: f32,
+    /// Flockmates closer than this push the creature away.
+    #[default(0.8)]
+    pub separation_radius: f32,
+}
+
+impl Component for Steering {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -0,0 +1,194 @@
+use amethyst::{
+    assets::PrefabData,
+    derive::PrefabData,
+    ecs::{Component, DenseVecStorage, Entity, WriteStorage},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+/// Maps a need between 0 and 1 to a score between 0 and 1.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Curve {
+    /// The same score whatever the need.
+    Constant(f32),
+    Linear {
+        slope: f32,
+        offset: f32,
+    },
+    /// The need raised to `exponent`. Exponents above 1 only care about pressing needs, exponents
+    /// below 1 react to the slightest need.
+    Power {
+        exponent: f32,
+    },
+    /// An S-curve that rises around `midpoint`, the steeper the more sudden.
+    Logistic {
+        midpoint: f32,
+        steepness: f32,
+    },
+    /// 1 once the need reaches `threshold`, 0 before.
+    Step {
+        threshold: f32,
+    },
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Curve::Linear {
+            slope: 1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+impl Curve {
+    pub fn evaluate(&self, need: f32) -> f32 {
+        let need = need.max(0.0).min(1.0);
+        let score = match *self {
+            Curve::Constant(score) => score,
+            Curve::Linear { slope, offset } => slope * need + offset,
+            Curve::Power { exponent } => need.powf(exponent),
+            Curve::Logistic {
+                midpoint,
+                steepness,
+            } => 1.0 / (1.0 + (-steepness * (need - midpoint)).exp()),
+            Curve::Step { threshold } => {
+                if need >= threshold {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        };
+        score.max(0.0).min(1.0)
+    }
+}
+
+/// The behaviours a creature with `UtilityAi` chooses from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Behavior {
+    /// Roam around.
+    Wander,
+    /// Look for prey or carrion and go for it.
+    Eat,
+    /// Run away from predators.
+    Flee,
+    /// Come to a halt and recover from fatigue.
+    Rest,
+    /// Settle down and have offspring, see `ReproductionSystem`.
+    Mate,
+}
+
+/// How pressing each need of a creature is, between 0 and 1.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Needs {
+    /// How empty the stomach is.
+    pub hunger: f32,
+    /// How close the closest predator is.
+    pub fear: f32,
+    pub fatigue: f32,
+    /// How ready the creature is to reproduce, 1 once it can.
+    pub mating: f32,
+}
+
+/// Lets a creature pick one behaviour at a time by scoring its needs, see `UtilitySystem`. Each
+/// need goes through its own curve; the behaviour with the highest score wins.
+#[derive(Clone, Debug, SmartDefault, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct UtilityAi {
+    /// Score of wandering around, the fallback when no need is pressing.
+    #[default(0.2)]
+    pub wander: f32,
+    /// Scores eating by hunger.
+    pub hunger: Curve,
+    /// Scores fleeing by fear.
+    pub fear: Curve,
+    /// Scores resting by fatigue.
+    pub fatigue: Curve,
+    /// Scores mating by mating urge.
+    #[default(Curve::Step { threshold: 1.0 })]
+    pub mating: Curve,
+    /// Added to the score of the current behaviour, so creatures do not flip-flop between two
+    /// behaviours that score about the same.
+    #[default(0.1)]
+    pub inertia: f32,
+    /// Fatigue gained every second spent moving at top speed.
+    #[default(0.02)]
+    pub fatigue_rate: f32,
+    /// Fatigue lost every second spent resting.
+    #[default(0.1)]
+    pub recovery_rate: f32,
+    /// Current fatigue of the creature, between 0 and 1.
+    pub fatigue_level: f32,
+}
+
+impl Component for UtilityAi {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl UtilityAi {
+    /// The behaviour with the highest score, given the needs of the creature and what it is
+    /// currently doing.
+    pub fn choose(&self, needs: &Needs, current: Option<Behavior>) -> Behavior {
+        let scores = [
+            (Behavior::Wander, self.wander),
+            (Behavior::Eat, self.hunger.evaluate(needs.hunger)),
+            (Behavior::Flee, self.fear.evaluate(needs.fear)),
+            (Behavior::Rest, self.fatigue.evaluate(needs.fatigue)),
+            (Behavior::Mate, self.mating.evaluate(needs.mating)),
+        ];
+        let mut best = (Behavior::Wander, std::f32::NEG_INFINITY);
+        for &(behavior, score) in scores.iter() {
+            let score = if Some(behavior) == current {
+                score + self.inertia
+            } else {
+                score
+            };
+            if score > best.1 {
+                best = (behavior, score);
+            }
+        }
+        best.0
+    }
+}
+
+/// The behaviour a creature with `UtilityAi` is currently carrying out.
+#[derive(Clone, Debug)]
+pub struct ActiveBehavior(pub Behavior);
+
+impl Component for ActiveBehavior {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pressing_needs_win_over_wandering() {
+        let utility = UtilityAi {
+            hunger: Curve::Logistic {
+                midpoint: 0.5,
+                steepness: 10.0,
+            },
+            fear: Curve::Power { exponent: 0.5 },
+            ..Default::default()
+        };
+        let needs = |hunger, fear| Needs {
+            hunger,
+            fear,
+            ..Default::default()
+        };
+
+        assert_eq!(utility.choose(&needs(0.0, 0.0), None), Behavior::Wander);
+        assert_eq!(utility.choose(&needs(0.8, 0.0), None), Behavior::Eat);
+        assert_eq!(utility.choose(&needs(0.8, 1.0), None), Behavior::Flee);
+        // Close calls stick with what the creature is doing.
+        assert_eq!(
+            utility.choose(&needs(0.8, 1.0), Some(Behavior::Eat)),
+            Behavior::Eat
+        );
+    }
+}
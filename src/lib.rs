@@ -0,0 +1,16 @@
+//! The Evoli ecosystem simulation. The `components`, `resources` and `systems` modules hold the
+//! simulation itself and `simulation::EvoliSimulationBundle` wires its systems into a dispatcher.
+//! Rendering, debug lines, UI, audio and the game states that use them are only built with the
+//! `render` feature, which is enabled by default.
+
+#[macro_use]
+extern crate log;
+
+pub mod components;
+#[cfg(feature = "render")]
+pub mod render_graph;
+pub mod resources;
+pub mod simulation;
+pub mod states;
+pub mod systems;
+pub mod utils;
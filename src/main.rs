@@ -1,6 +1,3 @@
-#[macro_use]
-extern crate log;
-
 use amethyst::assets::PrefabLoaderSystemDesc;
 use amethyst::{
     audio::{AudioBundle, DjSystem},
@@ -18,16 +15,9 @@ use amethyst::{
 use amethyst::renderer::plugins::{RenderPbr3D, RenderToWindow};
 use amethyst::renderer::RenderingBundle;
 
-mod components;
-mod render_graph;
-mod resources;
-mod states;
-mod systems;
-mod utils;
-
-use crate::components::{combat, creatures};
-use crate::resources::audio::Music;
-use crate::states::loading::LoadingState;
+use evolution_island::components::combat;
+use evolution_island::resources::audio::Music;
+use evolution_island::states::loading::LoadingState;
 
 fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
@@ -57,16 +47,9 @@ fn main() -> amethyst::Result<()> {
         .with_bundle(
             InputBundle::<StringBindings>::new().with_bindings_from_file(&key_bindings_path)?,
         )?
-        .with_system_desc(
-            PrefabLoaderSystemDesc::<creatures::CreaturePrefabData>::default(),
-            "creature_loader",
-            &[],
-        )
-        .with_system_desc(
-            GltfSceneLoaderSystemDesc::default(),
-            "gltf_loader",
-            &["creature_loader"],
-        )
+        // Creature prefabs are loaded by the states that use them, see `LoadingState` and
+        // `MainGameState`, so spawned creatures are instantiated in step with the simulation.
+        .with_system_desc(GltfSceneLoaderSystemDesc::default(), "gltf_loader", &[])
         .with_system_desc(
             PrefabLoaderSystemDesc::<combat::FactionPrefabData>::default(),
             "",
@@ -0,0 +1,59 @@
+use amethyst::config::Config;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::components::behavior_tree::BehaviorNode;
+
+/// Every behaviour tree creatures can use, by name. Each tree is declared in a RON file of its
+/// own; the name is the file name without its extension.
+#[derive(Clone, Debug, Default)]
+pub struct BehaviorTrees {
+    trees: HashMap<String, BehaviorNode>,
+}
+
+impl BehaviorTrees {
+    /// Loads every `.ron` file in `directory`. Trees that fail to load are left out.
+    pub fn load_directory(directory: impl AsRef<Path>) -> BehaviorTrees {
+        let mut trees = HashMap::new();
+        let entries = match std::fs::read_dir(directory.as_ref()) {
+            Ok(entries) => entries,
+            Err(error) => {
+                error!(
+                    "Failed to read behaviour trees from {:?}. Error: {:?}",
+                    directory.as_ref(),
+                    error
+                );
+                return BehaviorTrees { trees };
+            }
+        };
+        for path in entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+        {
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "ron")
+            {
+                continue;
+            }
+            let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            match BehaviorNode::load(&path) {
+                Ok(tree) => {
+                    trees.insert(name, tree);
+                }
+                Err(error) => error!(
+                    "Failed to load behaviour tree {:?}. Error: {:?}",
+                    path, error
+                ),
+            }
+        }
+        BehaviorTrees { trees }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&BehaviorNode> {
+        self.trees.get(name)
+    }
+}
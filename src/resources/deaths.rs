@@ -0,0 +1,38 @@
+use amethyst::ecs::{BitSet, Entity};
+
+/// The creatures that died during the current tick. A deleted entity still shows up in joins
+/// until the end of the tick, so every system that kills creatures checks in here first: a
+/// creature that would die of several causes in the same tick only dies of the first.
+#[derive(Debug, Default)]
+pub struct Deaths {
+    tick: u64,
+    deceased: BitSet,
+}
+
+impl Deaths {
+    /// Records the death of `entity` during `tick`, see `SimulationTime::ticks`. Returns `false`
+    /// if it already died during that tick.
+    pub fn record(&mut self, tick: u64, entity: Entity) -> bool {
+        if tick != self.tick {
+            self.tick = tick;
+            self.deceased.clear();
+        }
+        !self.deceased.add(entity.id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::ecs::{Builder, World, WorldExt};
+
+    #[test]
+    fn creatures_die_once_per_tick() {
+        let mut world = World::new();
+        let entity = world.create_entity().build();
+        let mut deaths = Deaths::default();
+        assert!(deaths.record(1, entity));
+        assert!(!deaths.record(1, entity));
+        assert!(deaths.record(2, entity));
+    }
+}
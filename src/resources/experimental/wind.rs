@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 /// Keeps track of the wind conditions in the world.
 /// Currently, wind is represented by a 2D vector.
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(default)]
 #[serde(deny_unknown_fields)]
 pub struct Wind {
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write, fs, io, path::Path};
+
+use crate::components::genetics::Genome;
+
+/// Everything known about a single creature in the family tree.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LineageRecord {
+    pub id: u64,
+    pub parent: Option<u64>,
+    pub creature_type: String,
+    /// Simulated seconds since the start of the simulation.
+    pub birth_time: f64,
+    pub death_time: Option<f64>,
+    pub genome: Genome,
+}
+
+/// The family tree of every creature with a `Genome` that ever lived in the simulation. Ids are
+/// handed out in order of birth and never reused, so they stay stable across save games.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LineageRegistry {
+    next_id: u64,
+    records: BTreeMap<u64, LineageRecord>,
+}
+
+impl LineageRegistry {
+    /// Records the birth of a creature and returns its lineage id.
+    pub fn register_birth(
+        &mut self,
+        parent: Option<u64>,
+        creature_type: String,
+        birth_time: f64,
+        genome: Genome,
+    ) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.records.insert(
+            id,
+            LineageRecord {
+                id,
+                parent,
+                creature_type,
+                birth_time,
+                death_time: None,
+                genome,
+            },
+        );
+        id
+    }
+
+    pub fn register_death(&mut self, id: u64, death_time: f64) {
+        if let Some(record) = self.records.get_mut(&id) {
+            record.death_time = Some(death_time);
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<&LineageRecord> {
+        self.records.get(&id)
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &LineageRecord> {
+        self.records.values()
+    }
+
+    /// The family tree in Newick format, one tree per founder. Nodes are labelled with their
+    /// creature type and id; branch lengths are the seconds between the birth of the parent and
+    /// the birth of the child.
+    pub fn to_newick(&self) -> String {
+        let mut children: BTreeMap<u64, Vec<&LineageRecord>> = BTreeMap::new();
+        for record in self.records.values() {
+            if let Some(parent) = record.parent {
+                children.entry(parent).or_default().push(record);
+            }
+        }
+        let mut newick = String::new();
+        for founder in self.records.values().filter(|record| {
+            record
+                .parent
+                .map_or(true, |parent| !self.records.contains_key(&parent))
+        }) {
+            write_newick_node(&mut newick, founder, None, &children);
+            newick.push_str(";\n");
+        }
+        newick
+    }
+
+    /// Every record as a JSON array.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.records.values().collect::<Vec<_>>())
+    }
+
+    /// Writes `lineage.nwk` and `lineage.json` into `directory`.
+    pub fn export(&self, directory: &Path) -> io::Result<()> {
+        fs::create_dir_all(directory)?;
+        fs::write(directory.join("lineage.nwk"), self.to_newick())?;
+        fs::write(directory.join("lineage.json"), self.to_json()?)?;
+        Ok(())
+    }
+}
+
+fn write_newick_node(
+    newick: &mut String,
+    record: &LineageRecord,
+    parent_birth_time: Option<f64>,
+    children: &BTreeMap<u64, Vec<&LineageRecord>>,
+) {
+    if let Some(children_of_record) = children.get(&record.id) {
+        newick.push('(');
+        for (i, child) in children_of_record.iter().enumerate() {
+            if i > 0 {
+                newick.push(',');
+            }
+            write_newick_node(newick, child, Some(record.birth_time), children);
+        }
+        newick.push(')');
+    }
+    write!(newick, "{}_{}", record.creature_type, record.id).expect("writing to a string");
+    if let Some(parent_birth_time) = parent_birth_time {
+        write!(newick, ":{:.3}", record.birth_time - parent_birth_time)
+            .expect("writing to a string");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newick_contains_one_tree_per_founder() {
+        let mut registry = LineageRegistry::default();
+        let founder =
+            registry.register_birth(None, "Herbivore".to_string(), 0.0, Genome::default());
+        let child = registry.register_birth(
+            Some(founder),
+            "Herbivore".to_string(),
+            10.0,
+            Genome::default(),
+        );
+        registry.register_birth(
+            Some(child),
+            "Herbivore".to_string(),
+            12.5,
+            Genome::default(),
+        );
+        registry.register_birth(
+            Some(founder),
+            "Herbivore".to_string(),
+            20.0,
+            Genome::default(),
+        );
+        registry.register_birth(None, "Carnivore".to_string(), 0.0, Genome::default());
+
+        assert_eq!(
+            registry.to_newick(),
+            "((Herbivore_2:2.500)Herbivore_1:10.000,Herbivore_3:20.000)Herbivore_0;\nCarnivore_4;\n"
+        );
+    }
+}
@@ -1,6 +1,17 @@
+#[cfg(feature = "render")]
 pub mod audio;
+pub mod behavior_trees;
+pub mod deaths;
 pub mod debug;
+pub mod lineage;
 pub mod prefabs;
+pub mod rng;
+pub mod run_directory;
+pub mod scenario;
+pub mod simulation_time;
+pub mod soil;
+pub mod statistics;
+pub mod sunlight;
 pub mod world_bounds;
 
 mod experimental;
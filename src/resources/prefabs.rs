@@ -1,18 +1,22 @@
 use crate::components::creatures::CreaturePrefabData;
+#[cfg(feature = "render")]
+use amethyst::ui::{UiLoader, UiPrefab};
 use amethyst::{
-    assets::{AssetStorage, Handle, Prefab, PrefabLoader, ProgressCounter, RonFormat},
+    assets::{AssetStorage, Format, Handle, Prefab, PrefabLoader, ProgressCounter, RonFormat},
     ecs::prelude::{World, WorldExt},
-    ui::{UiLoader, UiPrefab},
     utils::application_root_dir,
+    Error,
 };
 use std::collections::HashMap;
 use std::fs::read_dir;
 
+#[cfg(feature = "render")]
 #[derive(Default)]
 pub struct UiPrefabRegistry {
     pub prefabs: Vec<Handle<UiPrefab>>,
 }
 
+#[cfg(feature = "render")]
 impl UiPrefabRegistry {
     pub fn find(&self, world: &World, name: &str) -> Option<Handle<UiPrefab>> {
         let storage = world.read_resource::<AssetStorage<UiPrefab>>();
@@ -62,6 +66,27 @@ impl CreaturePrefabs {
     }
 }
 
+/// Reads creature prefabs like `RonFormat` does, but strips the render assets (gltf scenes) from
+/// them. Used by headless runs, where nothing would ever finish loading those assets.
+#[derive(Clone, Debug, Default)]
+pub struct DataOnlyPrefabFormat;
+
+impl Format<Prefab<CreaturePrefabData>> for DataOnlyPrefabFormat {
+    fn name(&self) -> &'static str {
+        "DATA_ONLY_PREFAB"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<Prefab<CreaturePrefabData>, Error> {
+        let mut prefab: Prefab<CreaturePrefabData> = RonFormat.import_simple(bytes)?;
+        for index in 0..prefab.len() {
+            if let Some(data) = prefab.entity(index).and_then(|entity| entity.data_mut()) {
+                data.strip_render_assets();
+            }
+        }
+        Ok(prefab)
+    }
+}
+
 fn make_name(subdirectory: &str, entry: &std::fs::DirEntry) -> String {
     let path_buffer = entry.path();
     let filename = path_buffer.file_name().unwrap();
@@ -72,6 +97,7 @@ fn make_name(subdirectory: &str, entry: &std::fs::DirEntry) -> String {
 // These prefabs are then stored in a resource of type CreaturePrefabs that is used by the spawner system.
 // At initialization time, we put temporary keys for the prefabs since they're not loaded yet.
 // When their loading is finished, we read the name of the entity inside to change the keys. This is done in the update_prefabs function.
+#[cfg(feature = "render")]
 pub fn initialize_prefabs(world: &mut World) -> ProgressCounter {
     let mut progress_counter = ProgressCounter::new();
     // load ui prefabs
@@ -97,37 +123,51 @@ pub fn initialize_prefabs(world: &mut World) -> ProgressCounter {
         world.insert(ui_prefab_registry);
     }
 
-    // load creature prefabs
-    {
-        let prefab_iter = {
-            let prefab_dir_path = application_root_dir()
-                .unwrap()
-                .into_os_string()
-                .into_string()
-                .unwrap()
-                + "/resources/prefabs/creatures";
-            let prefab_iter = read_dir(prefab_dir_path).unwrap();
-            prefab_iter.map(|prefab_dir_entry| {
-                world.exec(|loader: PrefabLoader<'_, CreaturePrefabData>| {
-                    loader.load(
-                        make_name("prefabs/creatures/", &prefab_dir_entry.unwrap()),
-                        RonFormat,
-                        &mut progress_counter,
-                    )
-                })
-            })
-        };
+    load_creature_prefabs(world, RonFormat, &mut progress_counter);
 
-        let mut creature_prefabs = CreaturePrefabs::default();
-        for (count, prefab) in prefab_iter.enumerate() {
-            creature_prefabs.insert("temp_prefab_".to_string() + &count.to_string(), prefab);
-        }
-        world.insert(creature_prefabs);
-    }
+    progress_counter
+}
 
+// Load the creature prefabs without any of their render assets. Used by headless runs, which
+// have neither a renderer nor a UI to load prefabs for.
+pub fn initialize_data_only_prefabs(world: &mut World) -> ProgressCounter {
+    let mut progress_counter = ProgressCounter::new();
+    load_creature_prefabs(world, DataOnlyPrefabFormat, &mut progress_counter);
     progress_counter
 }
 
+// Load all prefabs for the different creatures in the game with the given format.
+// These prefabs are then stored in a resource of type CreaturePrefabs that is used by the spawner system.
+fn load_creature_prefabs<F>(world: &mut World, format: F, progress_counter: &mut ProgressCounter)
+where
+    F: Format<Prefab<CreaturePrefabData>> + Clone,
+{
+    let prefab_iter = {
+        let prefab_dir_path = application_root_dir()
+            .unwrap()
+            .into_os_string()
+            .into_string()
+            .unwrap()
+            + "/resources/prefabs/creatures";
+        let prefab_iter = read_dir(prefab_dir_path).unwrap();
+        prefab_iter.map(|prefab_dir_entry| {
+            world.exec(|loader: PrefabLoader<'_, CreaturePrefabData>| {
+                loader.load(
+                    make_name("prefabs/creatures/", &prefab_dir_entry.unwrap()),
+                    format.clone(),
+                    &mut *progress_counter,
+                )
+            })
+        })
+    };
+
+    let mut creature_prefabs = CreaturePrefabs::default();
+    for (count, prefab) in prefab_iter.enumerate() {
+        creature_prefabs.insert("temp_prefab_".to_string() + &count.to_string(), prefab);
+    }
+    world.insert(creature_prefabs);
+}
+
 // Once the prefabs are loaded, this function is called to update the ekeys in the CreaturePrefabs struct.
 // We use the Named component of the entity to determine which key to use.
 pub fn update_prefabs(world: &mut World) {
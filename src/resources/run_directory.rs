@@ -0,0 +1,17 @@
+use std::path::{Path, PathBuf};
+
+/// The directory a simulation run writes its exports to, like the lineage of its creatures.
+#[derive(Clone, Debug)]
+pub struct RunDirectory {
+    path: PathBuf,
+}
+
+impl RunDirectory {
+    pub fn new(path: PathBuf) -> RunDirectory {
+        RunDirectory { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+use crate::resources::statistics::StatisticsConfig;
+
+/// Settings for a simulation run, read from `scenario.ron`.
+#[derive(SmartDefault, Deserialize, Serialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Seed for the `SimRng`. A random seed is picked when it is not set here or on the
+    /// command line with `--seed`.
+    pub seed: Option<u64>,
+    /// Directory, relative to the application root, in which every run gets a directory of its
+    /// own for its exports.
+    #[default("output".to_string())]
+    pub output_directory: String,
+    /// Sampling of the population statistics that are written to the run directory.
+    pub statistics: StatisticsConfig,
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+/// Length of a single simulation tick in seconds.
+pub const SIMULATION_STEP: f32 = 1.0 / 60.0;
+
+/// Simulation ticks a single frame may run before the remaining backlog is dropped. This keeps a
+/// slow frame from snowballing into ever longer frames; the simulation slows down instead.
+pub const MAX_STEPS_PER_FRAME: u32 = 16;
+
+/// The clock of the simulation. Unlike amethyst's `Time` it advances in fixed ticks, so the
+/// simulation systems behave the same no matter the frame rate or the time scale.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SimulationTime {
+    ticks: u64,
+    accumulator: f32,
+}
+
+impl SimulationTime {
+    /// Seconds simulated by a single tick. Always `SIMULATION_STEP`.
+    pub fn delta_seconds(&self) -> f32 {
+        SIMULATION_STEP
+    }
+
+    /// Number of ticks simulated so far.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Simulated seconds since the start of the simulation.
+    pub fn elapsed_seconds(&self) -> f64 {
+        self.ticks as f64 * f64::from(SIMULATION_STEP)
+    }
+
+    /// Adds (already scaled) frame time that still has to be simulated.
+    pub fn accumulate(&mut self, seconds: f32) {
+        self.accumulator =
+            (self.accumulator + seconds).min(SIMULATION_STEP * MAX_STEPS_PER_FRAME as f32);
+    }
+
+    /// Takes a tick's worth of time out of the accumulator, if there is enough of it.
+    pub fn consume_step(&mut self) -> bool {
+        if self.accumulator >= SIMULATION_STEP {
+            self.accumulator -= SIMULATION_STEP;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Marks a tick as simulated.
+    pub fn advance(&mut self) {
+        self.ticks += 1;
+    }
+
+    /// How far the frame is between the last simulated tick and the next one, from 0 to 1.
+    pub fn interpolation_alpha(&self) -> f32 {
+        (self.accumulator / SIMULATION_STEP).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_at_four_times_speed_run_four_ticks_each() {
+        let mut time = SimulationTime::default();
+        let frames = 100;
+        for _ in 0..frames {
+            // `Time::delta_seconds` is already scaled by the time scale.
+            time.accumulate(SIMULATION_STEP * 4.0);
+            while time.consume_step() {
+                time.advance();
+            }
+        }
+        assert_eq!(time.ticks(), 4 * frames);
+    }
+}
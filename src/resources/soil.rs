@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::resources::world_bounds::WorldBounds;
+
+/// Nutrients in the soil at which fertility gives half of its largest boost.
+const HALF_SATURATION: f32 = 25.0;
+
+/// The nutrients in the soil, on a grid of square cells covering the `WorldBounds`. Decaying
+/// carcasses deposit their biomass here, plants take it up again.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SoilFertility {
+    left: f32,
+    bottom: f32,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    nutrients: Vec<f32>,
+}
+
+impl SoilFertility {
+    pub fn new(bounds: &WorldBounds, cell_size: f32) -> SoilFertility {
+        let columns = ((bounds.right - bounds.left) / cell_size).ceil().max(1.0) as usize;
+        let rows = ((bounds.top - bounds.bottom) / cell_size).ceil().max(1.0) as usize;
+        SoilFertility {
+            left: bounds.left,
+            bottom: bounds.bottom,
+            cell_size,
+            columns,
+            rows,
+            nutrients: vec![0.0; columns * rows],
+        }
+    }
+
+    fn cell(&self, x: f32, y: f32) -> Option<usize> {
+        if self.cell_size <= 0.0 {
+            return None;
+        }
+        let column = ((x - self.left) / self.cell_size).floor();
+        let row = ((y - self.bottom) / self.cell_size).floor();
+        if column < 0.0 || row < 0.0 {
+            return None;
+        }
+        let (column, row) = (column as usize, row as usize);
+        if column < self.columns && row < self.rows {
+            Some(row * self.columns + column)
+        } else {
+            None
+        }
+    }
+
+    /// Nutrients in the soil at the given position. Outside of the grid the soil is barren.
+    pub fn nutrients(&self, x: f32, y: f32) -> f32 {
+        self.cell(x, y).map_or(0.0, |cell| self.nutrients[cell])
+    }
+
+    /// Adds nutrients to the soil at the given position. Nutrients deposited outside of the grid
+    /// are lost.
+    pub fn deposit(&mut self, x: f32, y: f32, amount: f32) {
+        if let Some(cell) = self.cell(x, y) {
+            self.nutrients[cell] += amount;
+        }
+    }
+
+    /// Takes up to `amount` nutrients from the soil at the given position and returns how much
+    /// there was to take.
+    pub fn take(&mut self, x: f32, y: f32, amount: f32) -> f32 {
+        match self.cell(x, y) {
+            Some(cell) => {
+                let taken = amount.min(self.nutrients[cell]).max(0.0);
+                self.nutrients[cell] -= taken;
+                taken
+            }
+            None => 0.0,
+        }
+    }
+
+    /// How much fertile soil helps plants at the given position, from 1 on barren soil up to 2 on
+    /// very rich soil.
+    pub fn boost(&self, x: f32, y: f32) -> f32 {
+        let nutrients = self.nutrients(x, y);
+        1.0 + nutrients / (nutrients + HALF_SATURATION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nutrients_stay_in_their_cell() {
+        let mut soil = SoilFertility::new(&WorldBounds::new(-10.0, 10.0, -10.0, 10.0), 1.0);
+        soil.deposit(2.5, -3.5, 10.0);
+        soil.deposit(20.0, 0.0, 10.0);
+
+        assert_eq!(soil.nutrients(2.1, -3.9), 10.0);
+        assert_eq!(soil.nutrients(3.5, -3.5), 0.0);
+        assert_eq!(soil.nutrients(20.0, 0.0), 0.0);
+        assert_eq!(soil.take(2.5, -3.5, 4.0), 4.0);
+        assert_eq!(soil.take(2.5, -3.5, 10.0), 6.0);
+        assert_eq!(soil.boost(2.5, -3.5), 1.0);
+    }
+}
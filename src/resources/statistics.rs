@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+/// File format of the population statistics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum StatisticsFormat {
+    /// `population.csv`, one row per creature type or faction per sample.
+    Csv,
+    /// `population.jsonl`, one JSON object per sample.
+    JsonLines,
+}
+
+/// How the population is sampled, part of the `Scenario`.
+#[derive(Clone, Debug, SmartDefault, Deserialize, Serialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct StatisticsConfig {
+    /// Simulated seconds between two samples.
+    #[default(1.0)]
+    pub interval: f32,
+    #[default(StatisticsFormat::Csv)]
+    pub format: StatisticsFormat,
+}
+
+/// Statistics of the living members of a creature type or faction.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct GroupStats {
+    pub count: u32,
+    /// `None` when no member has a `Fullness`.
+    pub average_fullness: Option<f32>,
+    /// `None` when no member has a `Health`.
+    pub average_health: Option<f32>,
+    /// Births and deaths since the previous sample. Only counted per creature type, not per
+    /// faction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub births: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deaths: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PopulationSample {
+    /// Simulated seconds since the start of the simulation.
+    pub time: f64,
+    pub creatures: BTreeMap<String, GroupStats>,
+    pub factions: BTreeMap<String, GroupStats>,
+}
+
+impl PopulationSample {
+    fn write_csv(&self, out: &mut String) {
+        let groups = self
+            .creatures
+            .iter()
+            .map(|group| ("creature", group))
+            .chain(self.factions.iter().map(|group| ("faction", group)));
+        for (kind, (name, stats)) in groups {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{}",
+                self.time,
+                kind,
+                name,
+                stats.count,
+                optional(stats.average_fullness),
+                optional(stats.average_health),
+                optional(stats.births),
+                optional(stats.deaths),
+            )
+            .expect("writing to a string");
+        }
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+const CSV_HEADER: &str = "time,group,name,count,average_fullness,average_health,births,deaths\n";
+
+/// Samples of the population, taken every `StatisticsConfig::interval` simulated seconds by the
+/// `PopulationStatsSystem` and appended to a file in the run directory.
+#[derive(Default)]
+pub struct PopulationStats {
+    pub config: StatisticsConfig,
+    pub seconds_to_next_sample: f32,
+    /// Births and deaths per creature type since the last sample.
+    pub births: BTreeMap<String, u32>,
+    pub deaths: BTreeMap<String, u32>,
+    pub latest: Option<PopulationSample>,
+    file_started: bool,
+}
+
+impl PopulationStats {
+    pub fn new(config: StatisticsConfig) -> PopulationStats {
+        PopulationStats {
+            seconds_to_next_sample: config.interval,
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Counts a birth of a `creature_type` for the next sample.
+    pub fn record_birth(&mut self, creature_type: &str) {
+        *self.births.entry(creature_type.to_string()).or_default() += 1;
+    }
+
+    /// Counts a death of a `creature_type` for the next sample.
+    pub fn record_death(&mut self, creature_type: &str) {
+        *self.deaths.entry(creature_type.to_string()).or_default() += 1;
+    }
+
+    /// Completes a sample of the living `creatures` and `factions` with the births and deaths
+    /// counted since the previous sample, and starts counting anew. Creature types that died out
+    /// still report their last deaths.
+    pub fn finish_sample(
+        &mut self,
+        time: f64,
+        creatures: BTreeMap<String, GroupStats>,
+        factions: BTreeMap<String, GroupStats>,
+    ) -> PopulationSample {
+        let mut births = std::mem::take(&mut self.births);
+        let mut deaths = std::mem::take(&mut self.deaths);
+        let mut sample = PopulationSample {
+            time,
+            creatures,
+            factions,
+        };
+        for name in births.keys().chain(deaths.keys()) {
+            if !sample.creatures.contains_key(name) {
+                sample.creatures.insert(name.clone(), GroupStats::default());
+            }
+        }
+        for (name, group) in sample.creatures.iter_mut() {
+            group.births = Some(births.remove(name).unwrap_or(0));
+            group.deaths = Some(deaths.remove(name).unwrap_or(0));
+        }
+        sample
+    }
+
+    pub fn file_name(&self) -> &'static str {
+        match self.config.format {
+            StatisticsFormat::Csv => "population.csv",
+            StatisticsFormat::JsonLines => "population.jsonl",
+        }
+    }
+
+    /// Appends `sample` to the statistics file in `directory`. The file is replaced by the first
+    /// sample of a run.
+    pub fn write_sample(&mut self, directory: &Path, sample: &PopulationSample) -> io::Result<()> {
+        let path = directory.join(self.file_name());
+        let mut file = if self.file_started {
+            OpenOptions::new().append(true).open(path)?
+        } else {
+            fs::create_dir_all(directory)?;
+            let mut file = File::create(path)?;
+            if self.config.format == StatisticsFormat::Csv {
+                file.write_all(CSV_HEADER.as_bytes())?;
+            }
+            self.file_started = true;
+            file
+        };
+        let mut out = String::new();
+        match self.config.format {
+            StatisticsFormat::Csv => sample.write_csv(&mut out),
+            StatisticsFormat::JsonLines => {
+                out.push_str(&serde_json::to_string(sample)?);
+                out.push('\n');
+            }
+        }
+        file.write_all(out.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_count_births_and_deaths_since_the_previous_sample() {
+        let mut stats = PopulationStats::new(StatisticsConfig::default());
+        stats.record_birth("Herbivore");
+        stats.record_birth("Herbivore");
+        stats.record_death("Carnivore");
+
+        let mut creatures = BTreeMap::new();
+        creatures.insert(
+            "Herbivore".to_string(),
+            GroupStats {
+                count: 3,
+                ..Default::default()
+            },
+        );
+        let sample = stats.finish_sample(1.0, creatures.clone(), BTreeMap::new());
+        let herbivores = &sample.creatures["Herbivore"];
+        assert_eq!(herbivores.count, 3);
+        assert_eq!(herbivores.births, Some(2));
+        assert_eq!(herbivores.deaths, Some(0));
+        // The carnivores died out, but still report their last death.
+        let carnivores = &sample.creatures["Carnivore"];
+        assert_eq!(carnivores.count, 0);
+        assert_eq!(carnivores.deaths, Some(1));
+
+        let sample = stats.finish_sample(2.0, creatures, BTreeMap::new());
+        assert_eq!(sample.creatures["Herbivore"].births, Some(0));
+        assert!(!sample.creatures.contains_key("Carnivore"));
+    }
+}
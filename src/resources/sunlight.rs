@@ -0,0 +1,48 @@
+use amethyst::core::math::Vector3;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// The sun that lights the world, read from `sunlight.ron`. The windowed game renders it as a
+/// directional light and plants turn it into energy, see `Photosynthesis`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+#[serde(deny_unknown_fields)]
+pub struct Sunlight {
+    /// Direction the light travels in, like `DirectionalLight::direction`.
+    pub direction: Vector3<f32>,
+    /// Intensity of the rendered light at noon.
+    pub intensity: f32,
+    /// Simulated seconds of a full day and night. Without it the sun never moves.
+    pub day_length: Option<f32>,
+}
+
+impl Default for Sunlight {
+    fn default() -> Self {
+        Sunlight {
+            direction: Vector3::new(0.0, 0.3, -1.0),
+            intensity: 2.0,
+            day_length: None,
+        }
+    }
+}
+
+impl Sunlight {
+    /// How much of the day's light is left at `elapsed_seconds`, between 0 at midnight and 1 at
+    /// noon. A run starts at dawn.
+    pub fn daylight(&self, elapsed_seconds: f64) -> f32 {
+        match self.day_length {
+            Some(day_length) => {
+                let phase = 2.0 * PI * elapsed_seconds / f64::from(day_length);
+                (0.5 + 0.5 * phase.sin()) as f32
+            }
+            None => 1.0,
+        }
+    }
+
+    /// Light that reaches the ground at `elapsed_seconds`, between 0 and 1. The steeper the sun
+    /// shines down on the world, the more light plants get.
+    pub fn light_level(&self, elapsed_seconds: f64) -> f32 {
+        let elevation = (-self.direction.normalize().z).max(0.0);
+        elevation * self.daylight(elapsed_seconds)
+    }
+}
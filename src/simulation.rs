@@ -0,0 +1,427 @@
+use amethyst::{
+    config::Config,
+    core::{
+        math::{Rotation3, Vector3},
+        transform::Transform,
+        SystemBundle,
+    },
+    ecs::prelude::*,
+    shrev::EventChannel,
+    utils::application_root_dir,
+    Error,
+};
+use rand::Rng;
+use std::f32::consts::PI;
+
+use crate::{
+    resources::{
+        behavior_trees::BehaviorTrees, deaths::Deaths, lineage::LineageRegistry, rng::SimRng,
+        run_directory::RunDirectory, scenario::Scenario, simulation_time::SimulationTime,
+        soil::SoilFertility, statistics::PopulationStats, sunlight::Sunlight, wind::Wind,
+        world_bounds::WorldBounds,
+    },
+    systems::{
+        behaviors::{
+            decision::{
+                Carrion, ClosestSystem, Predator, Prey, QueryPredatorsAndPreySystem, SeekSystem,
+            },
+            obstacle::{ClosestObstacleSystem, Obstacle},
+        },
+        *,
+    },
+};
+
+/// Registers every system that drives the ecosystem itself: perception, decision making, movement,
+/// collision, digestion, combat, death and spawning. Nothing in here depends on a renderer, a
+/// window or player input, so the same graph runs in the windowed game, in headless runs and in
+/// any tool that depends on this crate.
+///
+/// The systems expect the resources inserted by `initialise_world_resources`, in particular the
+/// `SimRng` they draw all their random numbers from. Every dispatch simulates one fixed tick of
+/// `SimulationTime`; the caller advances the clock afterwards.
+#[derive(Default)]
+pub struct EvoliSimulationBundle;
+
+impl<'a, 'b> SystemBundle<'a, 'b> for EvoliSimulationBundle {
+    fn build(
+        self,
+        _world: &mut World,
+        builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> Result<(), Error> {
+        // Loaded and newborn entities have to be complete before anything else looks at them.
+        builder.add(restore::RestoreSystem, "restore_system", &[]);
+        builder.add(genetics::GenomeSystem, "genome_system", &["restore_system"]);
+        builder.add(aging::AgingSystem, "aging_system", &["genome_system"]);
+        builder.add(
+            combat::FactionRelationsSystem,
+            "faction_relations_system",
+            &[],
+        );
+        builder.add_barrier();
+        builder.add(perception::SpatialGridSystem, "spatial_grid", &[]);
+        builder.add(
+            perception::EntityDetectionSystem,
+            "entity_detection",
+            &["spatial_grid"],
+        );
+        builder.add(
+            QueryPredatorsAndPreySystem,
+            "query_predators_and_prey_system",
+            &[],
+        );
+        builder.add(ClosestObstacleSystem, "closest_obstacle_system", &[]);
+        builder.add(
+            ClosestSystem::<Prey>::default(),
+            "closest_prey_system",
+            &["query_predators_and_prey_system", "entity_detection"],
+        );
+        builder.add(
+            ClosestSystem::<Predator>::default(),
+            "closest_predator_system",
+            &["query_predators_and_prey_system", "entity_detection"],
+        );
+        builder.add(
+            ClosestSystem::<Carrion>::default(),
+            "closest_carrion_system",
+            &["query_predators_and_prey_system", "entity_detection"],
+        );
+        builder.add(
+            memory::MemorySystem,
+            "memory_system",
+            &[
+                "closest_prey_system",
+                "closest_predator_system",
+                "closest_carrion_system",
+            ],
+        );
+        builder.add(
+            behaviors::scavenge::ScavengeSystem,
+            "scavenge_system",
+            &["memory_system"],
+        );
+        builder.add(
+            behaviors::utility::UtilitySystem,
+            "utility_system",
+            &["scavenge_system", "memory_system"],
+        );
+        builder.add(
+            behaviors::behavior_tree::BehaviorTreeSystem::default(),
+            "behavior_tree_system",
+            &["utility_system"],
+        );
+        builder.add(
+            behaviors::steering::SteeringSystem,
+            "steering_system",
+            &["behavior_tree_system", "spatial_grid"],
+        );
+        builder.add(
+            SeekSystem::<Prey>::new(Rotation3::from_axis_angle(&Vector3::z_axis(), 0.0), 1.0),
+            "seek_prey_system",
+            &["steering_system"],
+        );
+        builder.add(
+            SeekSystem::<Carrion>::new(Rotation3::from_axis_angle(&Vector3::z_axis(), 0.0), 1.0),
+            "seek_carrion_system",
+            &["steering_system"],
+        );
+        builder.add(
+            SeekSystem::<Predator>::new(
+                // 180 degrees, run away!
+                Rotation3::from_axis_angle(&Vector3::z_axis(), std::f32::consts::PI),
+                1.0,
+            ),
+            "avoid_predator_system",
+            &["steering_system"],
+        );
+        builder.add(
+            SeekSystem::<Obstacle>::new(
+                // 120 degrees. A little more than perpendicular so the creature
+                // tries to steer away from the wall rather than just follow it.
+                Rotation3::from_axis_angle(&Vector3::z_axis(), 2f32 * std::f32::consts::FRAC_PI_3),
+                5.0,
+            ),
+            "avoid_obstacle_system",
+            &["closest_obstacle_system"],
+        );
+        builder.add(behaviors::ricochet::RicochetSystem, "ricochet_system", &[]);
+        builder.add(
+            behaviors::wander::WanderSystem,
+            "wander_system",
+            &[
+                "seek_prey_system",
+                "seek_carrion_system",
+                "avoid_predator_system",
+                "avoid_obstacle_system",
+                "ricochet_system",
+            ],
+        );
+        builder.add(
+            movement::MovementSystem,
+            "movement_system",
+            &["wander_system"],
+        );
+        builder.add(
+            collision::CollisionSystem,
+            "collision_system",
+            &["movement_system"],
+        );
+        builder.add(
+            collision::EnforceBoundsSystem,
+            "enforce_bounds_system",
+            &["movement_system"],
+        );
+        builder.add(digestion::DigestionSystem, "digestion_system", &[]);
+        builder.add(
+            photosynthesis::PhotosynthesisSystem,
+            "photosynthesis_system",
+            &["digestion_system"],
+        );
+        builder.add(
+            reproduction::ReproductionSystem,
+            "reproduction_system",
+            &["digestion_system"],
+        );
+        builder.add(
+            death::StarvationSystem,
+            "starvation_system",
+            &["digestion_system", "photosynthesis_system"],
+        );
+        builder.add(combat::CooldownSystem, "cooldown_system", &[]);
+        builder.add(combat::LungeSystem, "lunge_system", &["movement_system"]);
+        builder.add(
+            combat::FindAttackSystem,
+            "find_attack_system",
+            &["cooldown_system", "lunge_system", "spatial_grid"],
+        );
+        builder.add(
+            combat::PerformDefaultAttackSystem::default(),
+            "perform_default_attack_system",
+            &["find_attack_system"],
+        );
+        builder.add(
+            death::DeathByHealthSystem::default(),
+            "death_by_health_system",
+            &["perform_default_attack_system"],
+        );
+        builder.add(
+            regeneration::RegenerationSystem,
+            "regeneration_system",
+            &["starvation_system", "death_by_health_system"],
+        );
+        builder.add(
+            lineage::LineageSystem::default(),
+            "lineage_system",
+            &["starvation_system", "death_by_health_system"],
+        );
+        builder.add(
+            decay::DecaySystem::default(),
+            "decay_system",
+            &["death_by_health_system"],
+        );
+        builder.add(
+            death::CarcassSystem::default(),
+            "carcass_system",
+            &["death_by_health_system"],
+        );
+        builder.add(
+            swarm_behavior::SwarmSpawnSystem::default(),
+            "swarm_spawn",
+            &[],
+        );
+        builder.add(
+            topplegrass::TopplegrassSpawnSystem::default(),
+            "topplegrass_spawn_system",
+            &[],
+        );
+        builder.add(
+            topplegrass::TopplingSystem::default(),
+            "toppling_system",
+            &[],
+        );
+        builder.add(gravity::GravitySystem::default(), "gravity_system", &[]);
+        builder.add(
+            seeds::SeedDispersalSystem,
+            "seed_dispersal_system",
+            &["photosynthesis_system"],
+        );
+        builder.add(
+            seeds::GerminationSystem,
+            "germination_system",
+            &["movement_system", "gravity_system", "spatial_grid"],
+        );
+        builder.add(
+            out_of_bounds::OutOfBoundsDespawnSystem::default(),
+            "out_of_bounds_despawn_system",
+            &[],
+        );
+        builder.add(
+            swarm_behavior::SwarmBehaviorSystem::default(),
+            "swarm_behavior",
+            &[],
+        );
+        builder.add(
+            swarm_behavior::SwarmCenterSystem::default(),
+            "swarm_center",
+            &[],
+        );
+        builder.add(
+            spawner::CreatureSpawnerSystem::default(),
+            "creature_spawner",
+            &["germination_system", "swarm_spawn", "reproduction_system"],
+        );
+        builder.add(
+            statistics::PopulationStatsSystem::default(),
+            "population_stats_system",
+            &["lineage_system", "carcass_system", "creature_spawner"],
+        );
+        Ok(())
+    }
+}
+
+/// Side of a cell of the `SoilFertility` grid.
+const SOIL_CELL_SIZE: f32 = 1.0;
+
+// Resources the simulation systems expect to find in the world, shared by every loading state.
+// A seed given on the command line takes precedence over the one in the scenario file.
+pub fn initialise_world_resources(world: &mut World, config_path: &str, seed: Option<u64>) {
+    let scenario_path = config_path.to_string() + "/scenario.ron";
+    let mut scenario = Scenario::load(scenario_path).unwrap_or_else(|error| {
+        error!(
+            "Failed to load scenario from config file. Using Scenario::default() instead. Error: {:?}",
+            error
+        );
+        Scenario::default()
+    });
+    scenario.seed = seed.or(scenario.seed);
+    world.insert(scenario);
+    world.insert(WorldBounds::new(-10.0, 10.0, -10.0, 10.0));
+    let wind_config_path = config_path.to_string() + "/wind.ron";
+    let wind_config = Wind::load(wind_config_path).unwrap_or_else(|error| {
+        error!(
+            "Failed to load wind resource from config file. Using Wind::default() instead. Error: {:?}",
+            error
+        );
+        Wind::default()
+    });
+    world.insert(wind_config);
+
+    let sunlight_config_path = config_path.to_string() + "/sunlight.ron";
+    let sunlight_config = Sunlight::load(sunlight_config_path).unwrap_or_else(|error| {
+        error!(
+            "Failed to load sunlight resource from config file. Using Sunlight::default() instead. Error: {:?}",
+            error
+        );
+        Sunlight::default()
+    });
+    world.insert(sunlight_config);
+
+    world.insert(BehaviorTrees::load_directory(
+        config_path.to_string() + "/behaviors",
+    ));
+
+    reset_simulation(world);
+}
+
+// Start a new run of the simulation: a fresh clock, random numbers, soil, lineage and statistics,
+// exported to a run directory of their own. Every run without a seed in the `Scenario` gets a new
+// random one.
+pub fn reset_simulation(world: &mut World) {
+    let (seed, statistics, output_directory) = {
+        let scenario = world.read_resource::<Scenario>();
+        (
+            scenario.seed.unwrap_or_else(rand::random),
+            scenario.statistics.clone(),
+            scenario.output_directory.clone(),
+        )
+    };
+    info!("Simulation seed: {}", seed);
+    world.insert(SimRng::new(seed));
+    world.insert(SimulationTime::default());
+    world.insert(Deaths::default());
+    world.insert(LineageRegistry::default());
+    world.insert(PopulationStats::new(statistics));
+    world.insert(RunDirectory::new(
+        application_root_dir()
+            .expect("failed to find the application root")
+            .join(output_directory)
+            .join(format!("seed-{}", seed)),
+    ));
+    let soil = SoilFertility::new(&world.read_resource::<WorldBounds>(), SOIL_CELL_SIZE);
+    world.insert(soil);
+}
+
+// Find the value of a `--seed <number>` argument.
+pub fn seed_argument(args: impl Iterator<Item = String>) -> Option<u64> {
+    let mut args = args.skip_while(|arg| arg != "--seed").skip(1);
+    args.next().map(|value| {
+        value
+            .parse()
+            .expect("--seed expects an unsigned 64 bit number")
+    })
+}
+
+// Scatter the starting plants across the world. The spawn events are picked up by the
+// `CreatureSpawnerSystem` on the next dispatch.
+pub fn spawn_initial_plants(world: &mut World) {
+    for _ in 0..25 {
+        let mut transform = random_transform(world, "initial_plants", 0.01);
+        // Plants are scaled by the `PhotosynthesisSystem` as they grow.
+        let rotation = world
+            .write_resource::<SimRng>()
+            .stream("initial_plants")
+            .gen_range(0.0f32, PI);
+        transform.set_rotation_euler(0.0, 0.0, rotation);
+        // TODO unfortunate naming here; plants are not creatures...OrganismSpawnEvent or just SpawnEvent?
+        // I would go for something more generic than OrganismSpawnEvent; for example,
+        // Topplegrass isn't really one organism, but more of a set of organisms, both dead and alive.
+        spawn_creature(world, "Plant", transform);
+    }
+}
+
+// Scatter the first generation of animals across the world. Every other animal descends from
+// them through the `ReproductionSystem`.
+pub fn spawn_founders(world: &mut World) {
+    for (creature_type, count) in &[("Herbivore", 10), ("Carnivore", 3), ("Scavenger", 3)] {
+        for _ in 0..*count {
+            let mut transform = random_transform(world, "founders", 0.02);
+            transform.set_scale(Vector3::new(0.4, 0.4, 0.4));
+            spawn_creature(world, creature_type, transform);
+        }
+    }
+}
+
+fn random_transform(world: &World, stream: &str, z: f32) -> Transform {
+    let bounds = world.read_resource::<WorldBounds>();
+    let mut sim_rng = world.write_resource::<SimRng>();
+    let rng = sim_rng.stream(stream);
+    let mut transform = Transform::default();
+    transform.set_translation_xyz(
+        rng.gen_range(bounds.left, bounds.right),
+        rng.gen_range(bounds.bottom, bounds.top),
+        z,
+    );
+    transform
+}
+
+fn spawn_creature(world: &mut World, creature_type: &str, transform: Transform) {
+    let entity = world.create_entity().with(transform).build();
+    world
+        .write_resource::<EventChannel<spawner::CreatureSpawnEvent>>()
+        .single_write(spawner::CreatureSpawnEvent {
+            creature_type: creature_type.to_string(),
+            entity,
+            parent: None,
+        });
+}
+
+// Write the lineage of the creatures into the run directory.
+pub fn export_lineage(world: &World) {
+    let directory = world.read_resource::<RunDirectory>();
+    match world
+        .read_resource::<LineageRegistry>()
+        .export(directory.path())
+    {
+        Ok(()) => info!("Exported the lineage to {:?}", directory.path()),
+        Err(error) => error!("Failed to export the lineage: {}", error),
+    }
+}
@@ -0,0 +1,146 @@
+use amethyst::{
+    assets::ProgressCounter,
+    core::{ArcThreadPool, Named, SystemBundle},
+    ecs::prelude::*,
+    prelude::*,
+};
+use std::collections::BTreeMap;
+
+use crate::{
+    components::{combat::load_factions, creatures::CreatureTag},
+    resources::{
+        prefabs::{initialize_data_only_prefabs, update_prefabs},
+        simulation_time::{SimulationTime, SIMULATION_STEP},
+        spatial_grid::SpatialGrid,
+    },
+    simulation::{
+        export_lineage, initialise_world_resources, spawn_founders, spawn_initial_plants,
+        EvoliSimulationBundle,
+    },
+};
+
+/// How often, in simulated seconds, the population is written to the log.
+const REPORT_INTERVAL: f32 = 10.0;
+
+/// Loads factions and creature prefabs as plain data, without any render assets, then hands
+/// over to the `SimulationState`.
+pub struct HeadlessLoadingState {
+    config_path: String,
+    duration: f32,
+    seed: Option<u64>,
+    prefab_loading_progress: Option<ProgressCounter>,
+}
+
+impl HeadlessLoadingState {
+    pub fn new(config_path: String, duration: f32, seed: Option<u64>) -> Self {
+        HeadlessLoadingState {
+            config_path,
+            duration,
+            seed,
+            prefab_loading_progress: None,
+        }
+    }
+}
+
+impl SimpleState for HeadlessLoadingState {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        load_factions(data.world);
+        self.prefab_loading_progress = Some(initialize_data_only_prefabs(data.world));
+        initialise_world_resources(data.world, &self.config_path, self.seed);
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        data.data.update(&data.world);
+        if let Some(ref counter) = self.prefab_loading_progress.as_ref() {
+            if counter.num_failed() > 0 {
+                error!("Failed to load {} prefabs", counter.num_failed());
+                return Trans::Quit;
+            }
+            if counter.is_complete() {
+                self.prefab_loading_progress = None;
+                update_prefabs(&mut data.world);
+                return Trans::Switch(Box::new(SimulationState::new(data.world, self.duration)));
+            }
+        }
+
+        Trans::None
+    }
+}
+
+/// Runs the simulation systems for a fixed amount of simulated time, one tick per frame and as
+/// fast as the machine allows, then quits.
+pub struct SimulationState {
+    dispatcher: Dispatcher<'static, 'static>,
+    ticks_left: u64,
+    ticks_since_report: u64,
+}
+
+impl SimulationState {
+    pub fn new(world: &mut World, duration: f32) -> Self {
+        let pool = (&*world.read_resource::<ArcThreadPool>()).clone();
+        let mut dispatcher_builder = DispatcherBuilder::new().with_pool(pool);
+        EvoliSimulationBundle
+            .build(world, &mut dispatcher_builder)
+            .expect("failed to add the simulation systems");
+        SimulationState {
+            dispatcher: dispatcher_builder.build(),
+            ticks_left: (duration / SIMULATION_STEP).ceil() as u64,
+            ticks_since_report: 0,
+        }
+    }
+
+    fn report_population(&self, world: &World) {
+        let mut population = BTreeMap::new();
+        for (name, _) in (
+            &world.read_storage::<Named>(),
+            &world.read_storage::<CreatureTag>(),
+        )
+            .join()
+        {
+            *population.entry(name.name.to_string()).or_insert(0) += 1;
+        }
+        info!(
+            "{} ticks left, population: {:?}",
+            self.ticks_left, population
+        );
+    }
+}
+
+impl SimpleState for SimulationState {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        info!("start headless simulation");
+
+        self.dispatcher.setup(data.world);
+        data.world.insert(SpatialGrid::new(1.0f32));
+        data.world.register::<CreatureTag>();
+
+        spawn_initial_plants(data.world);
+        spawn_founders(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        self.report_population(data.world);
+        export_lineage(data.world);
+        info!("stop headless simulation");
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        if self.ticks_left == 0 {
+            return Trans::Quit;
+        }
+
+        // Every frame advances the world by exactly one tick, however long the frame really took.
+        self.dispatcher.dispatch(&data.world);
+        data.world.write_resource::<SimulationTime>().advance();
+        data.data.update(&data.world);
+        self.ticks_left -= 1;
+
+        self.ticks_since_report += 1;
+        if self.ticks_since_report as f32 * SIMULATION_STEP >= REPORT_INTERVAL {
+            self.ticks_since_report = 0;
+            self.report_population(data.world);
+        }
+
+        Trans::None
+    }
+}
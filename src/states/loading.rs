@@ -2,16 +2,17 @@ use crate::{
     resources::{
         audio::initialise_audio,
         prefabs::{initialize_prefabs, update_prefabs},
-        wind::*,
-        world_bounds::WorldBounds,
     },
+    simulation::{initialise_world_resources, seed_argument},
     states::{main_game::MainGameState, menu::MenuState},
 };
 use std::env;
 
-use crate::components::combat::load_factions;
+use crate::components::{combat::load_factions, creatures::CreaturePrefabData};
 use amethyst::{
-    assets::ProgressCounter,
+    assets::{PrefabLoaderSystem, PrefabLoaderSystemDesc, ProgressCounter},
+    core::SystemDesc,
+    ecs::RunNow,
     prelude::*,
     renderer::debug_drawing::{DebugLines, DebugLinesParams},
 };
@@ -21,6 +22,8 @@ const SKIP_MENU_ARG: &str = "no_menu";
 pub struct LoadingState {
     config_path: String,
     prefab_loading_progress: Option<ProgressCounter>,
+    // Processes the creature prefabs while they load.
+    creature_loader: Option<PrefabLoaderSystem<CreaturePrefabData>>,
 }
 
 impl Default for LoadingState {
@@ -28,6 +31,7 @@ impl Default for LoadingState {
         LoadingState {
             config_path: "".to_string(),
             prefab_loading_progress: None,
+            creature_loader: None,
         }
     }
 }
@@ -37,6 +41,7 @@ impl LoadingState {
         LoadingState {
             config_path,
             prefab_loading_progress: None,
+            creature_loader: None,
         }
     }
 }
@@ -44,22 +49,20 @@ impl LoadingState {
 impl SimpleState for LoadingState {
     fn on_start(&mut self, mut data: StateData<GameData>) {
         load_factions(data.world);
+        self.creature_loader =
+            Some(PrefabLoaderSystemDesc::<CreaturePrefabData>::default().build(data.world));
         self.prefab_loading_progress = Some(initialize_prefabs(&mut data.world));
         initialise_audio(data.world);
         data.world.insert(DebugLinesParams { line_width: 1.0 });
 
         data.world.insert(DebugLines::new());
-        data.world
-            .insert(WorldBounds::new(-10.0, 10.0, -10.0, 10.0));
-        let wind_config_path = self.config_path.clone() + "/wind.ron";
-        let wind_config = Wind::load(wind_config_path).unwrap_or_else(|error| {
-            error!("Failed to load wind resource from config file. Using Wind::default() instead. Error: {:?}", error);
-            Wind::default()
-        });
-        data.world.insert(wind_config);
+        initialise_world_resources(data.world, &self.config_path, seed_argument(env::args()));
     }
 
     fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        if let Some(creature_loader) = self.creature_loader.as_mut() {
+            creature_loader.run_now(data.world);
+        }
         data.data.update(&data.world);
         if let Some(ref counter) = self.prefab_loading_progress.as_ref() {
             println!(
@@ -1,7 +1,11 @@
 use amethyst;
 use amethyst::{
-    core::math::{clamp, Rotation3, Vector3},
-    core::{transform::Transform, ArcThreadPool, Time},
+    assets::{PrefabLoaderSystem, PrefabLoaderSystemDesc},
+    core::math::{clamp, Vector3},
+    core::{
+        transform::{Transform, TransformSystemDesc},
+        ArcThreadPool, SystemBundle, SystemDesc, Time,
+    },
     ecs::prelude::*,
     input::InputEvent,
     prelude::*,
@@ -17,33 +21,39 @@ use amethyst::{
 };
 use std::f32;
 
-use crate::systems::behaviors::decision::{
-    ClosestSystem, Predator, Prey, QueryPredatorsAndPreySystem, SeekSystem,
-};
-use crate::systems::behaviors::obstacle::{ClosestObstacleSystem, Obstacle};
 use crate::{
-    components::creatures::CreatureTag,
+    components::creatures::{CreaturePrefabData, CreatureTag},
     resources::{
-        debug::DebugConfig, prefabs::UiPrefabRegistry, spatial_grid::SpatialGrid,
-        world_bounds::WorldBounds,
+        debug::DebugConfig, prefabs::UiPrefabRegistry, simulation_time::SimulationTime,
+        spatial_grid::SpatialGrid, sunlight::Sunlight,
+    },
+    simulation::{
+        export_lineage, reset_simulation, spawn_founders, spawn_initial_plants,
+        EvoliSimulationBundle,
     },
     states::pause_menu::PauseMenuState,
     systems::*,
 };
-use rand::{thread_rng, Rng};
-use std::f32::consts::PI;
 
 const TIME_SCALE_FACTOR: f32 = 2.0;
 const TIME_SCALE_RANGE: (f32, f32) = (1.0 / 4.0, 1.0 * 4.0);
 
 pub struct MainGameState {
+    // Runs once per simulation tick, possibly several times per frame.
     dispatcher: Dispatcher<'static, 'static>,
+    // Runs once per frame.
+    frame_dispatcher: Dispatcher<'static, 'static>,
     debug_dispatcher: Dispatcher<'static, 'static>,
     ui_dispatcher: Dispatcher<'static, 'static>,
     ui: Option<Entity>,
     camera: Option<Entity>,
     paused: bool,
     desired_time_scale: f32,
+    restore_translation: interpolation::RestoreTranslationSystem,
+    interpolate_translation: interpolation::InterpolateTranslationSystem,
+    // Instantiates the creatures spawned by a tick before the next one runs. The game data has no
+    // creature loader of its own while this state runs, so nothing is instantiated twice.
+    creature_loader: PrefabLoaderSystem<CreaturePrefabData>,
 }
 
 impl MainGameState {
@@ -51,165 +61,39 @@ impl MainGameState {
         // For profiling, the dispatcher needs to specify the pool that is created for us by `ApplicationBuilder::new`.
         // This thread pool will include the necessary setup for `profile_scope`.
         let pool = (&*world.read_resource::<ArcThreadPool>()).clone();
+        let mut dispatcher_builder = DispatcherBuilder::new().with_pool(pool);
+        // The game data only updates the global matrices once per frame, but the simulation reads
+        // them on every tick.
+        dispatcher_builder.add(
+            TransformSystemDesc::default().build(world),
+            "simulation_transform_system",
+            &[],
+        );
+        dispatcher_builder.add_barrier();
+        EvoliSimulationBundle
+            .build(world, &mut dispatcher_builder)
+            .expect("failed to add the simulation systems");
+        dispatcher_builder.add_barrier();
+        dispatcher_builder.add(
+            interpolation::CaptureTranslationSystem,
+            "capture_translation_system",
+            &[],
+        );
+
         MainGameState {
-            dispatcher: DispatcherBuilder::new()
-                .with_pool(pool)
+            dispatcher: dispatcher_builder.build(),
+            frame_dispatcher: DispatcherBuilder::new()
                 .with(
                     camera_movement::CameraMovementSystem::default(),
                     "camera_movement",
                     &[],
                 )
-                .with(perception::SpatialGridSystem, "spatial_grid", &[])
-                .with(
-                    perception::EntityDetectionSystem,
-                    "entity_detection",
-                    &["spatial_grid"],
-                )
-                .with(
-                    QueryPredatorsAndPreySystem,
-                    "query_predators_and_prey_system",
-                    &[],
-                )
-                .with(ClosestObstacleSystem, "closest_obstacle_system", &[])
-                .with(
-                    ClosestSystem::<Prey>::default(),
-                    "closest_prey_system",
-                    &["query_predators_and_prey_system"],
-                )
-                .with(
-                    ClosestSystem::<Predator>::default(),
-                    "closest_predator_system",
-                    &["query_predators_and_prey_system"],
-                )
-                .with(
-                    SeekSystem::<Prey>::new(
-                        Rotation3::from_axis_angle(&Vector3::z_axis(), 0.0),
-                        1.0,
-                    ),
-                    "seek_prey_system",
-                    &["closest_prey_system"],
-                )
-                .with(
-                    SeekSystem::<Predator>::new(
-                        // 180 degrees, run away!
-                        Rotation3::from_axis_angle(&Vector3::z_axis(), std::f32::consts::PI),
-                        1.0,
-                    ),
-                    "avoid_predator_system",
-                    &["closest_predator_system"],
-                )
-                .with(
-                    SeekSystem::<Obstacle>::new(
-                        // 120 degrees. A little more than perpendicular so the creature
-                        // tries to steer away from the wall rather than just follow it.
-                        Rotation3::from_axis_angle(
-                            &Vector3::z_axis(),
-                            2f32 * std::f32::consts::FRAC_PI_3,
-                        ),
-                        5.0,
-                    ),
-                    "avoid_obstacle_system",
-                    &["closest_obstacle_system"],
-                )
-                .with(behaviors::ricochet::RicochetSystem, "ricochet_system", &[])
-                .with(
-                    behaviors::wander::WanderSystem,
-                    "wander_system",
-                    &[
-                        "seek_prey_system",
-                        "avoid_predator_system",
-                        "avoid_obstacle_system",
-                        "ricochet_system",
-                    ],
-                )
-                .with(
-                    movement::MovementSystem,
-                    "movement_system",
-                    &["wander_system"],
-                )
-                .with(
-                    collision::CollisionSystem,
-                    "collision_system",
-                    &["movement_system"],
-                )
-                .with(
-                    collision::EnforceBoundsSystem,
-                    "enforce_bounds_system",
-                    &["movement_system"],
-                )
-                .with(digestion::DigestionSystem, "digestion_system", &[])
-                .with(
-                    death::StarvationSystem,
-                    "starvation_system",
-                    &["digestion_system"],
-                )
-                .with(combat::CooldownSystem, "cooldown_system", &[])
-                .with(
-                    combat::FindAttackSystem::default(),
-                    "find_attack_system",
-                    &["cooldown_system"],
-                )
-                .with(
-                    combat::PerformDefaultAttackSystem::default(),
-                    "perform_default_attack_system",
-                    &["find_attack_system"],
-                )
-                .with(
-                    death::DeathByHealthSystem,
-                    "death_by_health_system",
-                    &["perform_default_attack_system"],
-                )
-                .with(
-                    death::CarcassSystem::default(),
-                    "carcass_system",
-                    &["death_by_health_system"],
-                )
-                .with(
-                    spawner::DebugSpawnTriggerSystem::default(),
-                    "debug_spawn_trigger",
-                    &[],
-                )
-                .with(
-                    swarm_behavior::SwarmSpawnSystem::default(),
-                    "swarm_spawn",
-                    &[],
-                )
-                .with(
-                    topplegrass::TopplegrassSpawnSystem::default(),
-                    "topplegrass_spawn_system",
-                    &[],
-                )
-                .with(
-                    topplegrass::TopplingSystem::default(),
-                    "toppling_system",
-                    &[],
-                )
-                .with(gravity::GravitySystem::default(), "gravity_system", &[])
-                .with(
-                    out_of_bounds::OutOfBoundsDespawnSystem::default(),
-                    "out_of_bounds_despawn_system",
-                    &[],
-                )
+                .with(photosynthesis::SunlightSystem, "sunlight_system", &[])
                 .with(
                     wind_control::DebugWindControlSystem::default(),
                     "wind_control_system",
                     &[],
                 )
-                .with(
-                    swarm_behavior::SwarmBehaviorSystem::default(),
-                    "swarm_behavior",
-                    &[],
-                )
-                .with(
-                    swarm_behavior::SwarmCenterSystem::default(),
-                    "swarm_center",
-                    &[],
-                )
-                .with(
-                    spawner::CreatureSpawnerSystem::default(),
-                    "creature_spawner",
-                    &["debug_spawn_trigger", "swarm_spawn"],
-                )
                 .build(),
             debug_dispatcher: DispatcherBuilder::new()
                 .with(debug::DebugSystem, "debug_system", &[])
@@ -243,6 +127,7 @@ impl MainGameState {
                     "debug_entity_detection",
                     &["debug_system"],
                 )
+                .with(memory::DebugMemorySystem, "debug_memory", &["debug_system"])
                 .build(),
             ui_dispatcher: DispatcherBuilder::new()
                 .with(
@@ -255,7 +140,38 @@ impl MainGameState {
             camera: None,
             paused: false,
             desired_time_scale: 1.0,
+            restore_translation: interpolation::RestoreTranslationSystem,
+            interpolate_translation: interpolation::InterpolateTranslationSystem,
+            creature_loader: PrefabLoaderSystemDesc::<CreaturePrefabData>::default().build(world),
+        }
+    }
+
+    // Run as many simulation ticks as the frame time, scaled by the time scale, asks for.
+    fn run_simulation(&mut self, world: &mut World) {
+        self.restore_translation.run_now(world);
+        // Creatures spawned outside of a tick, like the founders or a loaded save game, come
+        // alive before the first tick of the frame, even while paused.
+        self.instantiate_spawned(world);
+
+        let frame_seconds = world.read_resource::<Time>().delta_seconds();
+        world
+            .write_resource::<SimulationTime>()
+            .accumulate(frame_seconds);
+        while world.write_resource::<SimulationTime>().consume_step() {
+            self.dispatcher.dispatch(world);
+            world.write_resource::<SimulationTime>().advance();
+            world.maintain();
+            // A spawn takes exactly one tick to come alive, at any frame rate and time scale.
+            self.instantiate_spawned(world);
         }
+
+        self.interpolate_translation.run_now(world);
+    }
+
+    // Gives the creatures spawned so far the components of their prefab.
+    fn instantiate_spawned(&mut self, world: &mut World) {
+        self.creature_loader.run_now(world);
+        world.maintain();
     }
 
     // push desired_time_scale into effect
@@ -321,6 +237,9 @@ impl SimpleState for MainGameState {
         info!("start main game");
 
         self.dispatcher.setup(data.world);
+        self.frame_dispatcher.setup(data.world);
+        RunNow::setup(&mut self.restore_translation, data.world);
+        RunNow::setup(&mut self.interpolate_translation, data.world);
         self.debug_dispatcher.setup(data.world);
         self.ui_dispatcher.setup(data.world);
 
@@ -340,54 +259,26 @@ impl SimpleState for MainGameState {
         data.world.register::<CreatureTag>();
 
         // Add some plants
-        let (left, right, bottom, top) = {
-            let wb = data.world.read_resource::<WorldBounds>();
-            (wb.left, wb.right, wb.bottom, wb.top)
-        };
-        {
-            let mut rng = thread_rng();
-            for _ in 0..25 {
-                let x = rng.gen_range(left, right);
-                let y = rng.gen_range(bottom, top);
-                let scale = rng.gen_range(0.8f32, 1.2f32);
-                let rotation = rng.gen_range(0.0f32, PI);
-                let mut transform = Transform::default();
-                transform.set_translation_xyz(x, y, 0.01);
-                transform.set_scale(Vector3::new(scale, scale, 1.0));
-                transform.set_rotation_euler(0.0, 0.0, rotation);
-                let plant_entity = data.world.create_entity().with(transform).build();
-                let mut spawn_events = data
-                    .world
-                    .write_resource::<EventChannel<spawner::CreatureSpawnEvent>>();
-                // TODO unfortunate naming here; plants are not creatures...OrganismSpawnEvent or just SpawnEvent?
-                // I would go for something more generic than OrganismSpawnEvent; for example,
-                // Topplegrass isn't really one organism, but more of a set of organisms, both dead and alive.
-                spawn_events.single_write(spawner::CreatureSpawnEvent {
-                    creature_type: "Plant".to_string(),
-                    entity: plant_entity,
-                });
-            }
-        }
-        //insert single nushi
-        {
-            let mut rng = thread_rng();
-            let x = rng.gen_range(left, right);
-            let y = rng.gen_range(bottom, top);
-            let scale = 0.4f32;
+        spawn_initial_plants(data.world);
+        spawn_founders(data.world);
 
-            let mut transform = Transform::default();
-            transform.set_translation_xyz(x, y, 1.99);
-            transform.set_scale(Vector3::new(scale, scale, scale));
-
-            //let nushi_entity = data.world.create_entity().with(transform).build();
-            //let mut spawn_events = data
-            //.world
-            //.write_resource::<EventChannel<spawner::CreatureSpawnEvent>>();
-            //spawn_events.single_write(spawner::CreatureSpawnEvent {
-            //creature_type: "Nushi".to_string(),
-            //entity: nushi_entity,
-            //});
-        }
+        //insert single nushi
+        //{
+        //let (x, y) = random position inside the WorldBounds;
+        //let scale = 0.4f32;
+        //let mut transform = Transform::default();
+        //transform.set_translation_xyz(x, y, 1.99);
+        //transform.set_scale(Vector3::new(scale, scale, scale));
+        //let nushi_entity = data.world.create_entity().with(transform).build();
+        //let mut spawn_events = data
+        //.world
+        //.write_resource::<EventChannel<spawner::CreatureSpawnEvent>>();
+        //spawn_events.single_write(spawner::CreatureSpawnEvent {
+        //creature_type: "Nushi".to_string(),
+        //entity: nushi_entity,
+        //parent: None,
+        //});
+        //}
 
         {
             let scale = 1.05f32;
@@ -401,15 +292,19 @@ impl SimpleState for MainGameState {
             spawn_events.single_write(spawner::CreatureSpawnEvent {
                 creature_type: "Ground".to_string(),
                 entity: ground_entity,
+                parent: None,
             });
         }
 
         // Setup directional light (sun)
-        let light_component = Light::Directional(DirectionalLight {
-            color: Srgb::new(1.0, 1.0, 1.0),
-            intensity: 2.0f32,
-            direction: Vector3::new(0.0, 0.3, -1.0),
-        });
+        let light_component = {
+            let sunlight = data.world.read_resource::<Sunlight>();
+            Light::Directional(DirectionalLight {
+                color: Srgb::new(1.0, 1.0, 1.0),
+                intensity: sunlight.intensity,
+                direction: sunlight.direction,
+            })
+        };
         data.world.create_entity().with(light_component).build();
 
         data.world
@@ -451,6 +346,10 @@ impl SimpleState for MainGameState {
     fn on_stop(&mut self, data: StateData<GameData>) {
         info!("stop main game");
 
+        export_lineage(data.world);
+        // The next game starts a run of its own.
+        reset_simulation(data.world);
+
         if let Some(ui) = self.ui {
             if data.world.delete_entity(ui).is_ok() {
                 self.ui = None;
@@ -488,7 +387,8 @@ impl SimpleState for MainGameState {
     }
 
     fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
-        self.dispatcher.dispatch(&data.world);
+        self.run_simulation(data.world);
+        self.frame_dispatcher.dispatch(&data.world);
 
         for (db_comp,) in (&mut data.world.write_storage::<DebugLinesComponent>(),).join() {
             db_comp.clear();
@@ -1,7 +1,13 @@
+#[cfg(feature = "render")]
 pub mod controls;
+pub mod headless;
+#[cfg(feature = "render")]
 pub mod loading;
+#[cfg(feature = "render")]
 pub mod main_game;
+#[cfg(feature = "render")]
 pub mod menu;
+#[cfg(feature = "render")]
 pub mod pause_menu;
 
 //use amethyst::{
@@ -1,5 +1,6 @@
 use crate::resources::prefabs::UiPrefabRegistry;
 use crate::states::menu::MenuState;
+use crate::utils::save_game::{load_game, save_game};
 use amethyst::{
     ecs::Entity,
     prelude::*,
@@ -13,16 +14,22 @@ pub struct PauseMenuState {
     // button entities are created in on_start() and destroyed in on_stop()
     // if there is an invalid Entity that could be assigned to these by default, that'd be better than using Option
     resume_button: Option<Entity>,
+    save_button: Option<Entity>,
+    load_button: Option<Entity>,
     exit_to_main_menu_button: Option<Entity>,
     root: Option<Entity>,
 }
 
 const PAUSE_MENU_ID: &str = "pause_menu";
 const RESUME_BUTTON_ID: &str = "resume";
+const SAVE_BUTTON_ID: &str = "save";
+const LOAD_BUTTON_ID: &str = "load";
 const EXIT_TO_MAIN_MENU_BUTTON_ID: &str = "exit_to_main_menu";
 
 // load the pause_menu.ron prefab then instantiate it
 // if the "resume" button is clicked, goto MainGameState
+// if the "save" button is clicked, save the world and stay in the pause menu
+// if the "load" button is clicked, replace the world with the saved one and goto MainGameState
 // if the "exit_to_main_menu" button is clicked, remove the pause and main game states and go to MenuState.
 impl<'a> SimpleState for PauseMenuState {
     fn on_start(&mut self, data: StateData<GameData>) {
@@ -44,6 +51,8 @@ impl<'a> SimpleState for PauseMenuState {
             }
         }
         self.resume_button = None;
+        self.save_button = None;
+        self.load_button = None;
         self.exit_to_main_menu_button = None;
     }
 
@@ -55,6 +64,19 @@ impl<'a> SimpleState for PauseMenuState {
             }) => {
                 if Some(target) == self.resume_button {
                     Trans::Pop
+                } else if Some(target) == self.save_button {
+                    if let Err(error) = save_game(data.world) {
+                        error!("Failed to save the game: {}", error);
+                    }
+                    Trans::None
+                } else if Some(target) == self.load_button {
+                    match load_game(data.world) {
+                        Ok(()) => Trans::Pop,
+                        Err(error) => {
+                            error!("Failed to load the game: {}", error);
+                            Trans::None
+                        }
+                    }
                 } else if Some(target) == self.exit_to_main_menu_button {
                     let mut state_transition_event_channel = data
                         .world
@@ -74,9 +96,15 @@ impl<'a> SimpleState for PauseMenuState {
     fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
         data.data.update(&data.world);
         // once deferred creation of the root ui entity finishes, look up buttons
-        if self.resume_button.is_none() || self.exit_to_main_menu_button.is_none() {
+        if self.resume_button.is_none()
+            || self.save_button.is_none()
+            || self.load_button.is_none()
+            || self.exit_to_main_menu_button.is_none()
+        {
             data.world.exec(|ui_finder: UiFinder<'_>| {
                 self.resume_button = ui_finder.find(RESUME_BUTTON_ID);
+                self.save_button = ui_finder.find(SAVE_BUTTON_ID);
+                self.load_button = ui_finder.find(LOAD_BUTTON_ID);
                 self.exit_to_main_menu_button = ui_finder.find(EXIT_TO_MAIN_MENU_BUTTON_ID);
             });
         }
@@ -0,0 +1,101 @@
+use amethyst::{core::transform::Transform, ecs::*, shrev::EventChannel};
+use rand::Rng;
+
+use crate::{
+    components::{
+        aging::{AdultTraits, Age},
+        combat::Damage,
+        creatures::Movement,
+    },
+    resources::{deaths::Deaths, rng::SimRng, simulation_time::SimulationTime},
+    systems::death::{CreatureDeathEvent, DeathCause},
+    utils::save_game::PendingRestore,
+};
+
+/// Ages every creature with an `Age`. Newborns get their lifespan, creatures that reach a new life
+/// stage change their size, speed and damage, and creatures that outlive their lifespan die.
+pub struct AgingSystem;
+
+impl<'s> System<'s> for AgingSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Age>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Movement>,
+        WriteStorage<'s, Damage>,
+        ReadStorage<'s, PendingRestore>,
+        Write<'s, EventChannel<CreatureDeathEvent>>,
+        Write<'s, Deaths>,
+        Read<'s, SimulationTime>,
+        WriteExpect<'s, SimRng>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut ages,
+            mut transforms,
+            mut movements,
+            mut damages,
+            pending_restores,
+            mut death_events,
+            mut deaths,
+            time,
+            mut sim_rng,
+        ): Self::SystemData,
+    ) {
+        let rng = sim_rng.stream("aging");
+        // Loaded creatures get their age back from the save game first.
+        for (entity, age, transform, _) in
+            (&entities, &mut ages, &mut transforms, !&pending_restores).join()
+        {
+            let adult_traits = age
+                .adult_traits
+                .get_or_insert_with(|| AdultTraits {
+                    scale: *transform.scale(),
+                    max_movement_speed: movements.get(entity).map(|m| m.max_movement_speed),
+                    damage: damages.get(entity).map(|d| d.damage),
+                })
+                .clone();
+            let (min_lifespan, max_lifespan) = (age.min_lifespan, age.max_lifespan);
+            let lifespan = *age.lifespan.get_or_insert_with(|| {
+                if max_lifespan > min_lifespan {
+                    rng.gen_range(min_lifespan, max_lifespan)
+                } else {
+                    min_lifespan
+                }
+            });
+
+            age.seconds += time.delta_seconds();
+            if age.seconds >= lifespan {
+                if deaths.record(time.ticks(), entity) {
+                    death_events.single_write(CreatureDeathEvent {
+                        deceased: entity,
+                        cause: DeathCause::OldAge,
+                    });
+                    let _ = entities.delete(entity);
+                }
+                continue;
+            }
+
+            let stage = age.stage_at(lifespan);
+            if age.stage == Some(stage) {
+                continue;
+            }
+            age.stage = Some(stage);
+            let modifiers = age.modifiers(stage);
+            transform.set_scale(adult_traits.scale * modifiers.scale);
+            if let (Some(movement), Some(speed)) =
+                (movements.get_mut(entity), adult_traits.max_movement_speed)
+            {
+                movement.max_movement_speed = speed * modifiers.speed;
+            }
+            if let (Some(damage), Some(adult_damage)) =
+                (damages.get_mut(entity), adult_traits.damage)
+            {
+                damage.damage = adult_damage * modifiers.damage;
+            }
+        }
+    }
+}
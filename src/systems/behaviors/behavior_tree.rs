@@ -0,0 +1,197 @@
+use amethyst::{
+    core::{
+        math::{Rotation3, Vector3},
+        transform::Transform,
+    },
+    ecs::*,
+};
+use rand_pcg::Pcg32;
+use std::collections::HashSet;
+use std::f32::consts::PI;
+
+use crate::{
+    components::{
+        behavior_tree::{Action, Agent, BehaviorTree, Condition, Status, Target},
+        creatures::{Movement, Wander},
+        digestion::Fullness,
+        utility::{ActiveBehavior, Behavior},
+    },
+    resources::{behavior_trees::BehaviorTrees, rng::SimRng, simulation_time::SimulationTime},
+    systems::behaviors::{
+        decision::{seek, Carrion, Closest, Predator, Prey},
+        utility::REST_BRAKING,
+        wander::wander_step,
+    },
+};
+
+/// Strength of the steering of seeking and fleeing creatures, the same the default
+/// `SeekSystem`s use.
+const STEERING_MAGNITUDE: f32 = 1.0;
+
+/// A creature as its behaviour tree sees it.
+struct Creature<'a> {
+    movement: &'a mut Movement,
+    wander: Option<&'a mut Wander>,
+    transform: &'a Transform,
+    fullness: Option<&'a Fullness>,
+    behavior: Option<Behavior>,
+    prey: Option<Vector3<f32>>,
+    predator: Option<Vector3<f32>>,
+    carrion: Option<Vector3<f32>>,
+    delta_time: f32,
+    rng: &'a mut Pcg32,
+}
+
+impl<'a> Creature<'a> {
+    fn closest(&self, target: Target) -> Option<Vector3<f32>> {
+        match target {
+            Target::Prey => self.prey,
+            Target::Predator => self.predator,
+            Target::Carrion => self.carrion,
+        }
+    }
+
+    fn steer(&mut self, distance: Option<Vector3<f32>>, angle: f32) -> Status {
+        match distance {
+            Some(distance) => {
+                seek(
+                    &mut *self.movement,
+                    &distance,
+                    &Rotation3::from_axis_angle(&Vector3::z_axis(), angle),
+                    STEERING_MAGNITUDE,
+                    self.delta_time,
+                );
+                Status::Running
+            }
+            None => Status::Failure,
+        }
+    }
+}
+
+impl<'a> Agent for Creature<'a> {
+    fn check(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Hungry(share) => self
+                .fullness
+                .map_or(false, |fullness| fullness.value < share * fullness.max),
+            Condition::Sees(target) => self.closest(*target).is_some(),
+            Condition::Behaving(behavior) => self.behavior == Some(*behavior),
+        }
+    }
+
+    fn act(&mut self, action: &Action) -> Status {
+        match action {
+            Action::Seek(target) => self.steer(self.closest(*target), 0.0),
+            Action::Flee(target) => self.steer(self.closest(*target), PI),
+            Action::Wander => match self.wander.as_mut() {
+                Some(wander) => {
+                    wander_step(
+                        wander,
+                        &mut *self.movement,
+                        self.transform,
+                        self.delta_time,
+                        &mut *self.rng,
+                    );
+                    Status::Running
+                }
+                None => Status::Failure,
+            },
+            Action::Eat => {
+                let food = match (self.carrion, self.prey) {
+                    (Some(carrion), Some(prey)) if prey.norm() < carrion.norm() => Some(prey),
+                    (Some(carrion), _) => Some(carrion),
+                    (None, prey) => prey,
+                };
+                self.steer(food, 0.0)
+            }
+            Action::Rest => {
+                self.movement.velocity *= (1.0 - REST_BRAKING * self.delta_time).max(0.0);
+                Status::Running
+            }
+        }
+    }
+}
+
+/// Ticks the behaviour tree of every creature with a `BehaviorTree`. The tree takes over from the
+/// default steering: the creature's targets are removed afterwards so the `SeekSystem`s leave it
+/// alone, and the `WanderSystem` skips it. Obstacles are still avoided. Creatures whose tree does
+/// not exist keep the default steering.
+#[derive(Default)]
+pub struct BehaviorTreeSystem {
+    /// Trees that were asked for but do not exist, reported only once.
+    missing: HashSet<String>,
+}
+
+impl<'s> System<'s> for BehaviorTreeSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, BehaviorTree>,
+        WriteStorage<'s, Movement>,
+        WriteStorage<'s, Wander>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Fullness>,
+        ReadStorage<'s, ActiveBehavior>,
+        WriteStorage<'s, Closest<Prey>>,
+        WriteStorage<'s, Closest<Predator>>,
+        WriteStorage<'s, Closest<Carrion>>,
+        Read<'s, BehaviorTrees>,
+        Read<'s, SimulationTime>,
+        WriteExpect<'s, SimRng>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            behavior_trees,
+            mut movements,
+            mut wanders,
+            transforms,
+            fullnesses,
+            active_behaviors,
+            mut closest_prey,
+            mut closest_predators,
+            mut closest_carrion,
+            trees,
+            time,
+            mut sim_rng,
+        ): Self::SystemData,
+    ) {
+        let rng = sim_rng.stream("behavior_tree");
+        for (entity, behavior_tree, movement, transform) in
+            (&entities, &behavior_trees, &mut movements, &transforms).join()
+        {
+            let tree = match trees.get(&behavior_tree.name) {
+                Some(tree) => tree,
+                None => {
+                    if self.missing.insert(behavior_tree.name.clone()) {
+                        error!(
+                            "Failed to find behaviour tree {:?}, using default steering",
+                            behavior_tree.name
+                        );
+                    }
+                    continue;
+                }
+            };
+            let mut creature = Creature {
+                movement,
+                wander: wanders.get_mut(entity),
+                transform,
+                fullness: fullnesses.get(entity),
+                behavior: active_behaviors.get(entity).map(|active| active.0),
+                prey: closest_prey.get(entity).map(|closest| closest.distance),
+                predator: closest_predators
+                    .get(entity)
+                    .map(|closest| closest.distance),
+                carrion: closest_carrion.get(entity).map(|closest| closest.distance),
+                delta_time: time.delta_seconds(),
+                rng: &mut *rng,
+            };
+            tree.tick(&mut creature);
+
+            closest_prey.remove(entity);
+            closest_predators.remove(entity);
+            closest_carrion.remove(entity);
+        }
+    }
+}
@@ -1,13 +1,25 @@
 use amethyst::{
-    core::{math::*, transform::Transform, Time},
+    core::{math::*, transform::Transform},
     ecs::*,
 };
 
 use std::f32;
 use std::marker::PhantomData;
 
-use crate::components::combat::{FactionPrey, HasFaction};
+use crate::components::combat::{
+    CarrionTag, FactionRelations, FactionRelationships, HasFaction, Relationship,
+};
 use crate::components::creatures::*;
+use crate::components::perception::{DetectedEntities, Perception};
+use crate::resources::{simulation_time::SimulationTime, spatial_grid::SpatialGrid};
+
+/// How far creatures without `Perception` look for the closest entity, see `ClosestSystem`.
+pub const CLOSEST_RANGE: f32 = 5.0;
+
+/// How far a creature looks for the closest entity.
+pub fn closest_range(perception: Option<&Perception>) -> f32 {
+    perception.map_or(CLOSEST_RANGE, |perception| perception.range)
+}
 
 /// A query is a component that contains the queried bit set that can be used to join with other components
 pub struct Query<T>(BitSet, PhantomData<T>);
@@ -26,57 +38,67 @@ impl<T> Query<T> {
 pub struct Prey;
 #[derive(Default)]
 pub struct Predator;
+#[derive(Default)]
+pub struct Carrion;
 
 /// Write prey/predator queries to the faction entities. For each faction
 /// we calculate the set of entities that they consider prey and the set of entities they
-/// consider as predators.
+/// consider as predators, from the `FactionRelations`. Factions that are feared count as
+/// predators too. Members of a faction with a `CarrionTag` are not alive; they go into the
+/// carrion query instead of the prey query.
 pub struct QueryPredatorsAndPreySystem;
 impl<'s> System<'s> for QueryPredatorsAndPreySystem {
     type SystemData = (
         Entities<'s>,
         ReadStorage<'s, HasFaction<Entity>>,
-        ReadStorage<'s, FactionPrey<Entity>>,
+        ReadStorage<'s, FactionRelationships>,
+        ReadStorage<'s, CarrionTag>,
+        Read<'s, FactionRelations>,
         WriteStorage<'s, Query<Prey>>,
         WriteStorage<'s, Query<Predator>>,
+        WriteStorage<'s, Query<Carrion>>,
     );
 
     fn run(
         &mut self,
-        (entities, has_faction, faction_preys_set, mut preys_query, mut predators_query): Self::SystemData,
+        (
+            entities,
+            has_faction,
+            faction_relationships,
+            carrion_tags,
+            relations,
+            mut preys_query,
+            mut predators_query,
+            mut carrion_query,
+        ): Self::SystemData,
     ) {
-        for (faction, _) in (&entities, &faction_preys_set).join() {
-            if !preys_query.contains(faction) {
-                preys_query
-                    .insert(faction, Query::<Prey>::new())
-                    .expect("unreachable: we just queried");
-            }
-            if !predators_query.contains(faction) {
-                predators_query
-                    .insert(faction, Query::<Predator>::new())
-                    .expect("unreachable: we just queried");
-            }
-
-            predators_query.get_mut(faction).unwrap().0.clear();
-        }
-
-        for (faction, faction_preys) in (&entities, &faction_preys_set).join() {
-            let preys = preys_query.get_mut(faction).unwrap();
-            preys.0.clear();
-            for (prey, prey_faction) in (&entities, &has_faction).join() {
-                if faction_preys.is_prey(&prey_faction.faction) {
-                    preys.0.add(prey.id());
-                }
-            }
-        }
-
-        for (predator, predator_faction) in (&entities, &has_faction).join() {
-            let predator_preys = faction_preys_set.get(predator_faction.faction).unwrap();
-            for (prey_faction, _) in (&entities, &faction_preys_set).join() {
-                if predator_preys.is_prey(&prey_faction) {
-                    let predators = predators_query.get_mut(prey_faction).unwrap();
-                    predators.0.add(predator.id());
+        for (faction, _) in (&entities, &faction_relationships).join() {
+            let mut preys = Query::<Prey>::new();
+            let mut predators = Query::<Predator>::new();
+            let mut carrion = Query::<Carrion>::new();
+            for (member, member_faction) in (&entities, &has_faction).join() {
+                match relations.get(faction, member_faction.faction) {
+                    Relationship::Prey if carrion_tags.contains(member_faction.faction) => {
+                        carrion.0.add(member.id());
+                    }
+                    Relationship::Prey => {
+                        preys.0.add(member.id());
+                    }
+                    Relationship::Predator | Relationship::Fear => {
+                        predators.0.add(member.id());
+                    }
+                    _ => {}
                 }
             }
+            preys_query
+                .insert(faction, preys)
+                .expect("unreachable: the faction is alive");
+            predators_query
+                .insert(faction, predators)
+                .expect("unreachable: the faction is alive");
+            carrion_query
+                .insert(faction, carrion)
+                .expect("unreachable: the faction is alive");
         }
     }
 }
@@ -84,6 +106,8 @@ impl<'s> System<'s> for QueryPredatorsAndPreySystem {
 /// A component that stores the distance to the closest entity. The type T is used to tag the entity.
 pub struct Closest<T> {
     pub distance: Vector3<f32>,
+    /// How fast the closest entity is moving, zero if it is not.
+    pub velocity: Vector3<f32>,
     _phantom: PhantomData<T>,
 }
 
@@ -91,9 +115,15 @@ impl<T> Closest<T> {
     pub fn new(distance: Vector3<f32>) -> Closest<T> {
         Closest {
             distance,
+            velocity: Vector3::zeros(),
             _phantom: PhantomData {},
         }
     }
+
+    pub fn with_velocity(mut self, velocity: Vector3<f32>) -> Closest<T> {
+        self.velocity = velocity;
+        self
+    }
 }
 
 impl<T> Component for Closest<T>
@@ -105,9 +135,10 @@ where
 
 /// A system that returns the closest entity of a query on the faction.
 /// To make use of this system, you should attach a `Query<T>` to a faction. The system will
-/// attach `Closest<T>` to all entities that have a faction where `Query<T>` is attached. The distance
-/// between the entity and the queried entity needs to be at least 5.0f32. If the distance is higher,
-/// `Closest<T>` will not be attached.
+/// attach `Closest<T>` to all entities that have a faction where `Query<T>` is attached. Creatures
+/// only pick from what they perceive: the `DetectedEntities` of creatures with `Perception`, and
+/// whatever is within `CLOSEST_RANGE` of the others. If nothing is close enough, `Closest<T>`
+/// will not be attached.
 #[derive(Default)]
 pub struct ClosestSystem<T: Default>(PhantomData<T>);
 
@@ -120,12 +151,26 @@ where
         ReadStorage<'s, Transform>,
         ReadStorage<'s, HasFaction<Entity>>,
         ReadStorage<'s, Query<T>>,
+        ReadStorage<'s, Movement>,
+        ReadStorage<'s, Perception>,
+        ReadStorage<'s, DetectedEntities>,
+        ReadExpect<'s, SpatialGrid>,
         WriteStorage<'s, Closest<T>>,
     );
 
     fn run(
         &mut self,
-        (entities, transforms, factions, faction_query, mut closest): Self::SystemData,
+        (
+            entities,
+            transforms,
+            factions,
+            faction_query,
+            movements,
+            perceptions,
+            detected_entities,
+            spatial_grid,
+            mut closest,
+        ): Self::SystemData,
     ) {
         for (entity, transform, faction) in (&entities, &transforms, &factions).join() {
             // Remove the old value. The referenced might have moved or has been deleted.
@@ -137,22 +182,42 @@ where
                 continue;
             }
 
+            let range = closest_range(perceptions.get(entity));
+            let nearby;
+            let perceived = match detected_entities.get(entity) {
+                Some(detected) => &detected.entities,
+                // Creatures that detect nothing themselves look around them.
+                None => {
+                    nearby = spatial_grid.query(transform, range);
+                    &nearby
+                }
+            };
+
             let mut closest_opt = None;
-            let mut min_sq_distance = 5.0f32.powi(2);
+            let mut min_sq_distance = range.powi(2);
 
-            for (_, query_transform) in (&query_entities.unwrap().0, &transforms).join() {
+            for (query_entity, _, query_transform) in (
+                &entities,
+                &query_entities.unwrap().0 & perceived,
+                &transforms,
+            )
+                .join()
+            {
                 let position = transform.translation();
                 let query_position = query_transform.translation();
                 let difference = query_position - position;
                 let sq_distance = difference.magnitude_squared();
                 if sq_distance < min_sq_distance {
                     min_sq_distance = sq_distance;
-                    closest_opt = Some(difference);
+                    closest_opt = Some((difference, query_entity));
                 }
             }
 
-            if let Some(c) = closest_opt {
-                let closest_component = Closest::new(c);
+            if let Some((c, closest_entity)) = closest_opt {
+                let velocity = movements
+                    .get(closest_entity)
+                    .map_or_else(Vector3::zeros, |movement| movement.velocity);
+                let closest_component = Closest::new(c).with_velocity(velocity);
                 closest
                     .insert(entity, closest_component)
                     .expect("unreachable: we just queried");
@@ -187,19 +252,36 @@ where
     type SystemData = (
         Entities<'s>,
         ReadStorage<'s, Closest<T>>,
-        Read<'s, Time>,
+        Read<'s, SimulationTime>,
         WriteStorage<'s, Movement>,
     );
 
     fn run(&mut self, (_entities, closest_things, time, mut movements): Self::SystemData) {
         let delta_time = time.delta_seconds();
         for (movement, closest) in (&mut movements, &closest_things).join() {
-            if closest.distance.norm() < f32::EPSILON {
-                continue;
-            }
-            let target_velocity = closest.distance.normalize() * self.attraction_magnitude;
-            let steering_force = target_velocity - movement.velocity;
-            movement.velocity += self.attraction_modifier * steering_force * delta_time;
+            seek(
+                movement,
+                &closest.distance,
+                &self.attraction_modifier,
+                self.attraction_magnitude,
+                delta_time,
+            );
         }
     }
 }
+
+/// Steers `movement` towards something at `distance`, turned by `attraction_modifier`.
+pub fn seek(
+    movement: &mut Movement,
+    distance: &Vector3<f32>,
+    attraction_modifier: &Rotation3<f32>,
+    attraction_magnitude: f32,
+    delta_time: f32,
+) {
+    if distance.norm() < f32::EPSILON {
+        return;
+    }
+    let target_velocity = distance.normalize() * attraction_magnitude;
+    let steering_force = target_velocity - movement.velocity;
+    movement.velocity += attraction_modifier * steering_force * delta_time;
+}
@@ -1,4 +1,8 @@
+pub mod behavior_tree;
 pub mod decision;
 pub mod obstacle;
 pub mod ricochet;
+pub mod scavenge;
+pub mod steering;
+pub mod utility;
 pub mod wander;
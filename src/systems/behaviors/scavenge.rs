@@ -0,0 +1,41 @@
+use amethyst::ecs::*;
+
+use crate::{
+    components::{digestion::Fullness, scavenger::Scavenger},
+    systems::behaviors::decision::{Carrion, Closest, Prey},
+};
+
+/// Decides between carrion and live prey. Only scavengers seek out carrion, and a hungry
+/// scavenger that has carrion nearby ignores live prey until it has eaten.
+pub struct ScavengeSystem;
+
+impl<'s> System<'s> for ScavengeSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Scavenger>,
+        ReadStorage<'s, Fullness>,
+        WriteStorage<'s, Closest<Carrion>>,
+        WriteStorage<'s, Closest<Prey>>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, scavengers, fullnesses, mut closest_carrion, mut closest_prey): Self::SystemData,
+    ) {
+        let not_scavenging = (&entities, &closest_carrion, !&scavengers)
+            .join()
+            .map(|(entity, _, _)| entity)
+            .collect::<Vec<Entity>>();
+        for entity in not_scavenging {
+            closest_carrion.remove(entity);
+        }
+
+        for (entity, scavenger, fullness, _) in
+            (&entities, &scavengers, &fullnesses, &closest_carrion).join()
+        {
+            if fullness.value < scavenger.hunger_threshold * fullness.max {
+                closest_prey.remove(entity);
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use amethyst::{
+    core::{math::Vector3, transform::Transform},
+    ecs::*,
+};
+
+use crate::{
+    components::{
+        combat::HasFaction,
+        creatures::Movement,
+        steering::Steering,
+        utility::{ActiveBehavior, Behavior},
+    },
+    resources::{simulation_time::SimulationTime, spatial_grid::SpatialGrid},
+    systems::behaviors::decision::{Carrion, Closest, Predator, Prey},
+};
+
+/// Force that turns `velocity` into moving towards `offset` at `max_speed`. Unlike
+/// `decision::seek`, it leaves applying the force to the caller.
+pub fn seek_force(offset: &Vector3<f32>, velocity: &Vector3<f32>, max_speed: f32) -> Vector3<f32> {
+    match offset.try_normalize(std::f32::EPSILON) {
+        Some(direction) => direction * max_speed - velocity,
+        None => Vector3::zeros(),
+    }
+}
+
+/// Force that turns `velocity` into moving away from `offset` at `max_speed`.
+pub fn flee_force(offset: &Vector3<f32>, velocity: &Vector3<f32>, max_speed: f32) -> Vector3<f32> {
+    seek_force(&-offset, velocity, max_speed)
+}
+
+/// Where a target at `offset` moving at `target_velocity` will be by the time a creature moving
+/// at `max_speed` gets there, looking at most `prediction_time` seconds ahead.
+pub fn predict(
+    offset: &Vector3<f32>,
+    target_velocity: &Vector3<f32>,
+    max_speed: f32,
+    prediction_time: f32,
+) -> Vector3<f32> {
+    let look_ahead = if max_speed > 0.0 {
+        (offset.norm() / max_speed).min(prediction_time)
+    } else {
+        0.0
+    };
+    offset + target_velocity * look_ahead
+}
+
+/// Like `seek_force`, but slows down linearly once the target is within `slowdown_radius`.
+pub fn arrive(
+    offset: &Vector3<f32>,
+    velocity: &Vector3<f32>,
+    max_speed: f32,
+    slowdown_radius: f32,
+) -> Vector3<f32> {
+    let distance = offset.norm();
+    let speed = if distance < slowdown_radius {
+        max_speed * distance / slowdown_radius
+    } else {
+        max_speed
+    };
+    seek_force(offset, velocity, speed)
+}
+
+/// A member of the creature's flock, relative to the creature.
+pub struct Flockmate {
+    pub offset: Vector3<f32>,
+    pub velocity: Vector3<f32>,
+}
+
+/// Force away from flockmates closer than `radius`, the stronger the closer they are.
+pub fn separation(flock: &[Flockmate], radius: f32, max_speed: f32) -> Vector3<f32> {
+    flock
+        .iter()
+        .filter_map(|mate| {
+            let distance = mate.offset.norm();
+            if distance < radius {
+                let direction = mate
+                    .offset
+                    .try_normalize(std::f32::EPSILON)
+                    .unwrap_or_else(Vector3::zeros);
+                Some(-direction * (max_speed * (1.0 - distance / radius)))
+            } else {
+                None
+            }
+        })
+        .fold(Vector3::zeros(), |sum, force| sum + force)
+}
+
+/// Force that matches `velocity` to the average velocity of the flock.
+pub fn alignment(flock: &[Flockmate], velocity: &Vector3<f32>) -> Vector3<f32> {
+    if flock.is_empty() {
+        return Vector3::zeros();
+    }
+    let sum = flock
+        .iter()
+        .fold(Vector3::zeros(), |sum, mate| sum + mate.velocity);
+    sum / flock.len() as f32 - velocity
+}
+
+/// Force towards the center of the flock.
+pub fn cohesion(flock: &[Flockmate], velocity: &Vector3<f32>, max_speed: f32) -> Vector3<f32> {
+    if flock.is_empty() {
+        return Vector3::zeros();
+    }
+    let sum = flock
+        .iter()
+        .fold(Vector3::zeros(), |sum, mate| sum + mate.offset);
+    seek_force(&(sum / flock.len() as f32), velocity, max_speed)
+}
+
+/// The weighted separation, alignment and cohesion forces of `steering`. Resting and mating
+/// creatures stay put rather than keep up with their flock.
+pub fn flock_force(
+    flock: &[Flockmate],
+    steering: &Steering,
+    velocity: &Vector3<f32>,
+    max_speed: f32,
+    behavior: Option<Behavior>,
+) -> Vector3<f32> {
+    if let Some(Behavior::Rest) | Some(Behavior::Mate) = behavior {
+        return Vector3::zeros();
+    }
+    separation(flock, steering.separation_radius, max_speed) * steering.separation
+        + alignment(flock, velocity) * steering.alignment
+        + cohesion(flock, velocity, max_speed) * steering.cohesion
+}
+
+/// Steers every creature with `Steering`: it pursues its closest prey, evades its closest
+/// predator, arrives at its closest carrion and flocks with the members of its faction. The
+/// weighted forces are added to its velocity, `MovementSystem` clamps the speed afterwards. The
+/// creature's targets are removed so the `SeekSystem`s leave it alone.
+pub struct SteeringSystem;
+
+impl<'s> System<'s> for SteeringSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Steering>,
+        WriteStorage<'s, Movement>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, HasFaction<Entity>>,
+        ReadStorage<'s, ActiveBehavior>,
+        WriteStorage<'s, Closest<Prey>>,
+        WriteStorage<'s, Closest<Predator>>,
+        WriteStorage<'s, Closest<Carrion>>,
+        ReadExpect<'s, SpatialGrid>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            steerings,
+            mut movements,
+            transforms,
+            has_faction,
+            active_behaviors,
+            mut closest_prey,
+            mut closest_predators,
+            mut closest_carrion,
+            spatial_grid,
+            time,
+        ): Self::SystemData,
+    ) {
+        let mut forces = Vec::new();
+        for (entity, steering, movement, transform) in
+            (&entities, &steerings, &movements, &transforms).join()
+        {
+            let velocity = &movement.velocity;
+            let max_speed = movement.max_movement_speed;
+            let mut force = Vector3::zeros();
+
+            if let Some(prey) = closest_prey.get(entity) {
+                let target = predict(
+                    &prey.distance,
+                    &prey.velocity,
+                    max_speed,
+                    steering.prediction_time,
+                );
+                force += seek_force(&target, velocity, max_speed) * steering.pursue;
+            }
+            if let Some(predator) = closest_predators.get(entity) {
+                let threat = predict(
+                    &predator.distance,
+                    &predator.velocity,
+                    max_speed,
+                    steering.prediction_time,
+                );
+                force += flee_force(&threat, velocity, max_speed) * steering.evade;
+            }
+            if let Some(carrion) = closest_carrion.get(entity) {
+                force += arrive(
+                    &carrion.distance,
+                    velocity,
+                    max_speed,
+                    steering.slowdown_radius,
+                ) * steering.arrive;
+            }
+
+            let flocking =
+                steering.separation != 0.0 || steering.alignment != 0.0 || steering.cohesion != 0.0;
+            if let (true, Some(faction)) = (flocking, has_faction.get(entity)) {
+                let nearby = spatial_grid.query(transform, steering.flock_radius);
+                let flock = (&entities, &transforms, &movements, &has_faction, &nearby)
+                    .join()
+                    .filter(|(mate, _, _, mate_faction, _)| {
+                        *mate != entity && mate_faction.faction == faction.faction
+                    })
+                    .map(|(_, mate_transform, mate_movement, _, _)| Flockmate {
+                        offset: mate_transform.translation() - transform.translation(),
+                        velocity: mate_movement.velocity,
+                    })
+                    .filter(|mate| mate.offset.norm() < steering.flock_radius)
+                    .collect::<Vec<Flockmate>>();
+                let behavior = active_behaviors.get(entity).map(|active| active.0);
+                force += flock_force(&flock, steering, velocity, max_speed, behavior);
+            }
+
+            // Creatures steer across the ground only.
+            force.z = 0.0;
+            forces.push((entity, force));
+        }
+
+        let delta_time = time.delta_seconds();
+        for (entity, force) in forces {
+            if let Some(movement) = movements.get_mut(entity) {
+                movement.velocity += force * delta_time;
+            }
+            closest_prey.remove(entity);
+            closest_predators.remove(entity);
+            closest_carrion.remove(entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pursuers_lead_their_target() {
+        let offset = Vector3::new(4.0, 0.0, 0.0);
+        let target_velocity = Vector3::new(0.0, 1.0, 0.0);
+        let predicted = predict(&offset, &target_velocity, 2.0, 10.0);
+        assert_eq!(predicted, Vector3::new(4.0, 2.0, 0.0));
+        // The look-ahead is capped.
+        let predicted = predict(&offset, &target_velocity, 2.0, 0.5);
+        assert_eq!(predicted, Vector3::new(4.0, 0.5, 0.0));
+    }
+
+    #[test]
+    fn arriving_slows_down_near_the_target() {
+        let still = Vector3::zeros();
+        let far = arrive(&Vector3::new(4.0, 0.0, 0.0), &still, 2.0, 1.0);
+        let near = arrive(&Vector3::new(0.5, 0.0, 0.0), &still, 2.0, 1.0);
+        assert_eq!(far, Vector3::new(2.0, 0.0, 0.0));
+        assert_eq!(near, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn flocks_keep_apart_and_together() {
+        let flock = vec![
+            Flockmate {
+                offset: Vector3::new(0.5, 0.0, 0.0),
+                velocity: Vector3::new(0.0, 1.0, 0.0),
+            },
+            Flockmate {
+                offset: Vector3::new(3.0, 0.0, 0.0),
+                velocity: Vector3::new(0.0, 1.0, 0.0),
+            },
+        ];
+        let velocity = Vector3::zeros();
+        assert!(separation(&flock, 1.0, 1.0).x < 0.0);
+        assert!(cohesion(&flock, &velocity, 1.0).x > 0.0);
+        assert_eq!(alignment(&flock, &velocity), Vector3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn resting_creatures_do_not_flock() {
+        let flock = vec![Flockmate {
+            offset: Vector3::new(3.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 1.0, 0.0),
+        }];
+        let steering = Steering {
+            separation: 1.0,
+            alignment: 1.0,
+            cohesion: 1.0,
+            ..Default::default()
+        };
+        let velocity = Vector3::zeros();
+        let wandering = flock_force(&flock, &steering, &velocity, 1.0, Some(Behavior::Wander));
+        assert!(wandering.norm() > 0.0);
+        for behavior in &[Behavior::Rest, Behavior::Mate] {
+            let force = flock_force(&flock, &steering, &velocity, 1.0, Some(*behavior));
+            assert_eq!(force, Vector3::zeros());
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use amethyst::ecs::*;
+
+use crate::{
+    components::{
+        creatures::Movement,
+        digestion::Fullness,
+        perception::Perception,
+        reproduction::Reproduction,
+        utility::{ActiveBehavior, Behavior, Needs, UtilityAi},
+    },
+    resources::simulation_time::SimulationTime,
+    systems::behaviors::decision::{closest_range, Carrion, Closest, Predator, Prey},
+};
+
+/// Share of its speed a resting creature loses every second.
+pub const REST_BRAKING: f32 = 2.0;
+
+/// Scores the needs of every creature with `UtilityAi` and picks the behaviour it carries out.
+/// Behaviours that were not picked are held back: the targets of the seek systems are removed,
+/// wandering stops while fleeing, resting or mating, and only mating creatures reproduce.
+pub struct UtilitySystem;
+
+impl<'s> System<'s> for UtilitySystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, UtilityAi>,
+        WriteStorage<'s, ActiveBehavior>,
+        WriteStorage<'s, Movement>,
+        ReadStorage<'s, Fullness>,
+        ReadStorage<'s, Reproduction>,
+        ReadStorage<'s, Perception>,
+        WriteStorage<'s, Closest<Prey>>,
+        WriteStorage<'s, Closest<Carrion>>,
+        WriteStorage<'s, Closest<Predator>>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut utilities,
+            mut active_behaviors,
+            mut movements,
+            fullnesses,
+            reproductions,
+            perceptions,
+            mut closest_prey,
+            mut closest_carrion,
+            mut closest_predators,
+            time,
+        ): Self::SystemData,
+    ) {
+        let delta_time = time.delta_seconds();
+        for (entity, utility) in (&entities, &mut utilities).join() {
+            let current = active_behaviors.get(entity).map(|active| active.0);
+            let movement = movements.get_mut(entity);
+
+            // Moving tires a creature out, resting lets it recover.
+            utility.fatigue_level = if current == Some(Behavior::Rest) {
+                utility.fatigue_level - utility.recovery_rate * delta_time
+            } else {
+                let exertion = movement
+                    .as_ref()
+                    .filter(|movement| movement.max_movement_speed > 0.0)
+                    .map_or(0.0, |movement| {
+                        movement.velocity.magnitude() / movement.max_movement_speed
+                    });
+                utility.fatigue_level + utility.fatigue_rate * exertion.min(1.0) * delta_time
+            }
+            .max(0.0)
+            .min(1.0);
+
+            let needs = Needs {
+                hunger: fullnesses.get(entity).map_or(0.0, |fullness| {
+                    1.0 - fullness.value / fullness.max.max(f32::EPSILON)
+                }),
+                fear: closest_predators.get(entity).map_or(0.0, |closest| {
+                    1.0 - closest.distance.magnitude() / closest_range(perceptions.get(entity))
+                }),
+                fatigue: utility.fatigue_level,
+                mating: match (reproductions.get(entity), fullnesses.get(entity)) {
+                    (Some(reproduction), Some(fullness)) if reproduction.time_left <= 0.0 => {
+                        fullness.value / reproduction.fullness_threshold.max(f32::EPSILON)
+                    }
+                    _ => 0.0,
+                },
+            };
+            let behavior = utility.choose(&needs, current);
+            active_behaviors
+                .insert(entity, ActiveBehavior(behavior))
+                .expect("Unreachable: the entity is alive");
+
+            if behavior != Behavior::Eat {
+                closest_prey.remove(entity);
+                closest_carrion.remove(entity);
+            }
+            if behavior != Behavior::Flee {
+                closest_predators.remove(entity);
+            }
+            if behavior == Behavior::Rest || behavior == Behavior::Mate {
+                if let Some(movement) = movement {
+                    movement.velocity *= (1.0 - REST_BRAKING * delta_time).max(0.0);
+                }
+            }
+        }
+    }
+}
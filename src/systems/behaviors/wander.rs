@@ -1,9 +1,19 @@
-use amethyst::core::{math::Point3, transform::Transform, Time};
+#[cfg(feature = "render")]
+use amethyst::core::math::Point3;
+use amethyst::core::transform::Transform;
 use amethyst::ecs::*;
+#[cfg(feature = "render")]
 use amethyst::renderer::{debug_drawing::DebugLinesComponent, palette::Srgba};
 
-use crate::components::creatures;
-use rand::{thread_rng, Rng};
+use crate::{
+    components::{
+        behavior_tree::BehaviorTree,
+        creatures,
+        utility::{ActiveBehavior, Behavior},
+    },
+    resources::{behavior_trees::BehaviorTrees, rng::SimRng, simulation_time::SimulationTime},
+};
+use rand::Rng;
 
 pub struct WanderSystem;
 impl<'s> System<'s> for WanderSystem {
@@ -11,38 +21,89 @@ impl<'s> System<'s> for WanderSystem {
         WriteStorage<'s, creatures::Wander>,
         WriteStorage<'s, creatures::Movement>,
         ReadStorage<'s, Transform>,
-        Read<'s, Time>,
+        ReadStorage<'s, ActiveBehavior>,
+        ReadStorage<'s, BehaviorTree>,
+        Read<'s, BehaviorTrees>,
+        Read<'s, SimulationTime>,
+        WriteExpect<'s, SimRng>,
     );
 
-    fn run(&mut self, (mut wanders, mut movements, locals, time): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            mut wanders,
+            mut movements,
+            locals,
+            active_behaviors,
+            behavior_trees,
+            trees,
+            time,
+            mut sim_rng,
+        ): Self::SystemData,
+    ) {
         let delta_time = time.delta_seconds();
-        let mut rng = thread_rng();
+        let rng = sim_rng.stream("wander");
 
-        for (wander, movement, local) in (&mut wanders, &mut movements, &locals).join() {
-            let position = local.translation();
-            let future_position = position + movement.velocity * 0.5;
+        for (wander, movement, local, active, behavior_tree) in (
+            &mut wanders,
+            &mut movements,
+            &locals,
+            active_behaviors.maybe(),
+            behavior_trees.maybe(),
+        )
+            .join()
+        {
+            // Creatures with a behaviour tree only wander when their tree says so. Without the
+            // tree they named, they fall back to wandering like any other creature.
+            if behavior_tree.map_or(false, |behavior_tree| {
+                trees.get(&behavior_tree.name).is_some()
+            }) {
+                continue;
+            }
+            // Only wandering and hungry creatures roam, the latter in search of food.
+            if let Some(ActiveBehavior(Behavior::Flee))
+            | Some(ActiveBehavior(Behavior::Rest))
+            | Some(ActiveBehavior(Behavior::Mate)) = active
+            {
+                continue;
+            }
+            wander_step(wander, movement, local, delta_time, rng);
+        }
+    }
+}
 
-            let direction = wander.get_direction();
-            let target = future_position + direction;
+/// Steers `movement` along a randomly turning direction.
+pub fn wander_step(
+    wander: &mut creatures::Wander,
+    movement: &mut creatures::Movement,
+    local: &Transform,
+    delta_time: f32,
+    rng: &mut impl Rng,
+) {
+    let position = local.translation();
+    let future_position = position + movement.velocity * 0.5;
 
-            let desired_velocity = target - position;
+    let direction = wander.get_direction();
+    let target = future_position + direction;
 
-            movement.velocity += desired_velocity * delta_time;
-            // Quick and dirty fix to keep entities from wandering into the ground if they target
-            // an entity not on the same z-level as themselves.
-            movement.velocity.z = 0.0;
+    let desired_velocity = target - position;
 
-            let change = 10.0;
-            if rng.gen::<bool>() {
-                wander.angle += change * delta_time; // Radians per second
-            } else {
-                wander.angle -= change * delta_time;
-            }
-        }
+    movement.velocity += desired_velocity * delta_time;
+    // Quick and dirty fix to keep entities from wandering into the ground if they target
+    // an entity not on the same z-level as themselves.
+    movement.velocity.z = 0.0;
+
+    let change = 10.0;
+    if rng.gen::<bool>() {
+        wander.angle += change * delta_time; // Radians per second
+    } else {
+        wander.angle -= change * delta_time;
     }
 }
 
+#[cfg(feature = "render")]
 pub struct DebugWanderSystem;
+#[cfg(feature = "render")]
 impl<'s> System<'s> for DebugWanderSystem {
     type SystemData = (
         ReadStorage<'s, creatures::Wander>,
@@ -1,6 +1,9 @@
+#[cfg(feature = "render")]
+use amethyst::core::math::Point3;
+#[cfg(feature = "render")]
 use amethyst::renderer::{debug_drawing::DebugLinesComponent, palette::Srgba};
 use amethyst::shrev::{EventChannel, ReaderId};
-use amethyst::{core::math::Point3, core::Transform, ecs::prelude::*};
+use amethyst::{core::Transform, ecs::prelude::*};
 use log::info;
 use std::f32;
 #[cfg(feature = "profiler")]
@@ -94,8 +97,10 @@ impl<'s> System<'s> for CollisionSystem {
     }
 }
 
+#[cfg(feature = "render")]
 pub struct DebugColliderSystem;
 
+#[cfg(feature = "render")]
 impl<'s> System<'s> for DebugColliderSystem {
     type SystemData = (
         ReadStorage<'s, collider::Circle>,
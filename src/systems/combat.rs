@@ -1,29 +1,47 @@
 use amethyst::{
-    core::Time,
+    core::{math::Vector2, transform::Transform},
     ecs::*,
     shrev::{EventChannel, ReaderId},
 };
 
+use crate::components::collider::Circle;
 use crate::components::combat;
-use crate::components::combat::{Cooldown, Damage, Health, Speed};
+use crate::components::combat::{
+    Armor, AttackRange, Cooldown, Damage, FactionRelations, FactionRelationships, Factions, Health,
+    Lunge, Speed,
+};
+use crate::components::creatures::Movement;
 use crate::components::digestion::{Fullness, Nutrition};
-use crate::systems::collision::CollisionEvent;
+use crate::resources::{rng::SimRng, simulation_time::SimulationTime, spatial_grid::SpatialGrid};
+use crate::utils::save_game::PendingRestore;
 //#[cfg(test)]
 //use amethyst::Error;
 //#[cfg(test)]
 //use amethyst_test::AmethystApplication;
+use rand::Rng;
+use std::cmp::Ordering;
 use std::time::Duration;
 
+/// Largest collider radius a target is expected to have.
+const MAX_RADIUS: f32 = 1.0;
+
 pub struct CooldownSystem;
 
 impl<'s> System<'s> for CooldownSystem {
-    type SystemData = (WriteStorage<'s, Cooldown>, Entities<'s>, Read<'s, Time>);
+    type SystemData = (
+        WriteStorage<'s, Cooldown>,
+        Entities<'s>,
+        Read<'s, SimulationTime>,
+    );
 
     fn run(&mut self, (mut cooldowns, entities, time): Self::SystemData) {
         let mut to_remove = Vec::new();
 
         for (mut cooldown, entity) in (&mut cooldowns, &*entities).join() {
-            match cooldown.time_left.checked_sub(time.delta_time()) {
+            match cooldown
+                .time_left
+                .checked_sub(Duration::from_secs_f32(time.delta_seconds()))
+            {
                 Some(time_left) => {
                     cooldown.time_left = time_left;
                 }
@@ -39,6 +57,27 @@ impl<'s> System<'s> for CooldownSystem {
     }
 }
 
+/// Resolves the relationships the factions declare into the `FactionRelations` matrix. Factions
+/// are instantiated from their prefab one by one, so the matrix is resolved again whenever a new
+/// faction appears.
+pub struct FactionRelationsSystem;
+
+impl<'s> System<'s> for FactionRelationsSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, FactionRelationships>,
+        Read<'s, Factions>,
+        Write<'s, FactionRelations>,
+    );
+
+    fn run(&mut self, (entities, relationships, factions, mut relations): Self::SystemData) {
+        if relations.resolved_factions() == factions.len() {
+            return;
+        }
+        *relations = FactionRelations::resolve(&factions, (&entities, &relationships).join());
+    }
+}
+
 ///
 ///
 ///
@@ -47,6 +86,26 @@ pub struct AttackEvent {
     pub defender: Entity,
 }
 
+/// What came of an `AttackEvent`, written by the `PerformDefaultAttackSystem` for every attack
+/// that was carried out.
+#[derive(Debug, Clone)]
+pub struct AttackResolvedEvent {
+    pub attacker: Entity,
+    pub defender: Entity,
+    pub hit: bool,
+    pub critical: bool,
+    /// Health the defender lost.
+    pub damage: f32,
+    /// Nutrition the attacker took from the defender.
+    pub nutrition: f32,
+    /// Whether the attack took the last of the defender's health.
+    pub killed: bool,
+}
+
+/// Resolves attacks. An attack may miss, see `combat::hit_chance`, or be a critical hit. A hit
+/// deals damage through the defender's `Armor` and lets the attacker eat as much of the
+/// defender's nutrition as it dealt damage, as far as it has room for it. The outcome is reported
+/// as an `AttackResolvedEvent`.
 #[derive(Default)]
 pub struct PerformDefaultAttackSystem {
     event_reader: Option<ReaderId<AttackEvent>>,
@@ -55,61 +114,96 @@ pub struct PerformDefaultAttackSystem {
 impl<'s> System<'s> for PerformDefaultAttackSystem {
     type SystemData = (
         Read<'s, EventChannel<AttackEvent>>,
+        Write<'s, EventChannel<AttackResolvedEvent>>,
         ReadStorage<'s, Damage>,
+        ReadStorage<'s, Armor>,
+        ReadStorage<'s, Movement>,
         WriteStorage<'s, Cooldown>,
         ReadStorage<'s, Speed>,
         WriteStorage<'s, Health>,
         WriteStorage<'s, Fullness>,
         WriteStorage<'s, Nutrition>,
+        WriteExpect<'s, SimRng>,
     );
 
     fn run(
         &mut self,
         (
             attack_events,
+            mut resolved_events,
             damages,
+            armors,
+            movements,
             mut cooldowns,
             speeds,
             mut healths,
             mut fullnesses,
             mut nutritions,
+            mut sim_rng,
         ): Self::SystemData,
     ) {
         let event_reader = self
             .event_reader
             .as_mut()
             .expect("`PerformDefaultAttackSystem::setup` was not called before `PerformDefaultAttackSystem::run`");
+        let rng = sim_rng.stream("combat");
 
         for event in attack_events.read(event_reader) {
-            let mut attack_set = BitSet::new();
-            attack_set.add(event.attacker.id());
-            let mut defender_set = BitSet::new();
-            defender_set.add(event.defender.id());
-
-            let mut cooldown = None;
-
-            for (damage, _, speed, _) in (&damages, !&cooldowns, &speeds, &attack_set).join() {
-                for (mut health, _) in (&mut healths, &defender_set).join() {
-                    health.value = health.value - damage.damage;
-                    cooldown = Some(Cooldown::new(Duration::from_millis(
-                        (1000.0 / speed.attacks_per_second) as u64,
-                    )));
-                }
+            if cooldowns.contains(event.attacker) {
+                continue;
             }
+            let (damage, speed) = match (damages.get(event.attacker), speeds.get(event.attacker)) {
+                (Some(damage), Some(speed)) => (damage, speed),
+                _ => continue,
+            };
+            let health = match healths.get_mut(event.defender) {
+                // Defenders without health left are already dead.
+                Some(health) if health.value >= f32::EPSILON => health,
+                _ => continue,
+            };
+            cooldowns
+                .insert(
+                    event.attacker,
+                    Cooldown::new(Duration::from_millis(
+                        (1000.0 / speed.attacks_per_second) as u64,
+                    )),
+                )
+                .expect("Unreachable: we are inserting now.");
 
-            for (mut fullness, _, damage) in (&mut fullnesses, &attack_set, &damages).join() {
-                for (nutrition, _) in (&mut nutritions, &defender_set).join() {
-                    let delta = nutrition.value.min(damage.damage);
-                    nutrition.value = nutrition.value - delta;
-                    fullness.value = fullness.value + delta;
-                }
+            let top_speed = |entity| movements.get(entity).map_or(0.0, |m| m.max_movement_speed);
+            let armor = armors.get(event.defender);
+            let chance =
+                combat::hit_chance(top_speed(event.attacker), top_speed(event.defender), armor);
+            let mut resolved = AttackResolvedEvent {
+                attacker: event.attacker,
+                defender: event.defender,
+                hit: false,
+                critical: false,
+                damage: 0.0,
+                nutrition: 0.0,
+                killed: false,
+            };
+            if rng.gen::<f32>() >= chance {
+                resolved_events.single_write(resolved);
+                continue;
             }
+            resolved.hit = true;
+            resolved.critical = rng.gen::<f32>() < damage.critical_chance;
+            resolved.damage = combat::damage_dealt(damage, armor, resolved.critical);
+            health.value -= resolved.damage;
+            resolved.killed = health.value < f32::EPSILON;
 
-            if let Some(value) = cooldown {
-                cooldowns
-                    .insert(event.attacker, value)
-                    .expect("Unreachable: we are inserting now.");
+            if let (Some(fullness), Some(nutrition)) = (
+                fullnesses.get_mut(event.attacker),
+                nutritions.get_mut(event.defender),
+            ) {
+                // The attacker eats no more than it has room for.
+                let room = (fullness.max - fullness.value).max(0.0);
+                resolved.nutrition = nutrition.value.min(resolved.damage).min(room);
+                nutrition.value -= resolved.nutrition;
+                fullness.value += resolved.nutrition;
             }
+            resolved_events.single_write(resolved);
         }
     }
 
@@ -123,65 +217,129 @@ impl<'s> System<'s> for PerformDefaultAttackSystem {
     }
 }
 
-#[derive(Default)]
-pub struct FindAttackSystem {
-    event_reader: Option<ReaderId<CollisionEvent>>,
+/// Counts down lunges and ends them once they are over.
+pub struct LungeSystem;
+
+impl<'s> System<'s> for LungeSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Lunge>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn run(&mut self, (entities, mut lunges, time): Self::SystemData) {
+        let mut to_remove = Vec::new();
+        for (entity, lunge) in (&entities, &mut lunges).join() {
+            lunge.time_left -= time.delta_seconds();
+            if lunge.time_left <= 0.0 {
+                to_remove.push(entity);
+            }
+        }
+        for entity in to_remove {
+            lunges.remove(entity);
+        }
+    }
 }
 
-// Determine if a collision will trigger an attack. If that is the case, generate an `AttackEvent`
+/// Finds the closest target every creature with an `AttackRange` can attack. Targets within reach
+/// are attacked with an `AttackEvent`, targets a little further away are lunged at.
+pub struct FindAttackSystem;
+
 impl<'s> System<'s> for FindAttackSystem {
     type SystemData = (
-        Read<'s, EventChannel<CollisionEvent>>,
-        Write<'s, EventChannel<AttackEvent>>,
+        Entities<'s>,
+        ReadStorage<'s, AttackRange>,
         ReadStorage<'s, combat::HasFaction<Entity>>,
-        ReadStorage<'s, combat::FactionPrey<Entity>>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Circle>,
+        ReadStorage<'s, Cooldown>,
+        ReadStorage<'s, PendingRestore>,
+        WriteStorage<'s, Movement>,
+        WriteStorage<'s, Lunge>,
+        Read<'s, FactionRelations>,
+        ReadExpect<'s, SpatialGrid>,
+        Write<'s, EventChannel<AttackEvent>>,
     );
 
     fn run(
         &mut self,
-        (collision_events, mut attack_events, has_faction, faction_preys): Self::SystemData,
+        (
+            entities,
+            attack_ranges,
+            has_faction,
+            transforms,
+            circles,
+            cooldowns,
+            pending_restores,
+            mut movements,
+            mut lunges,
+            relations,
+            spatial_grid,
+            mut attack_events,
+        ): Self::SystemData,
     ) {
-        let event_reader = self
-            .event_reader
-            .as_mut()
-            .expect("`FindAttackSystem::setup` was not called before `FindAttackSystem::run`");
-
-        for event in collision_events.read(event_reader) {
-            let opt_factions = has_faction
-                .get(event.entity_a)
-                .and_then(|a| has_faction.get(event.entity_b).map(|b| (a, b)));
-
-            if let Some((faction_a, faction_b)) = opt_factions {
-                let preys_a = faction_preys.get(faction_a.faction);
-                if let Some(preys) = preys_a {
-                    if preys.is_prey(&faction_b.faction) {
-                        attack_events.single_write(AttackEvent {
-                            attacker: event.entity_a,
-                            defender: event.entity_b,
-                        });
-                    }
-                }
+        let radius = |entity| circles.get(entity).map_or(0.0, |circle| circle.radius);
+        let mut new_lunges = Vec::new();
+        for (attacker, range, faction, transform, _, _) in (
+            &entities,
+            &attack_ranges,
+            &has_faction,
+            &transforms,
+            !&cooldowns,
+            !&pending_restores,
+        )
+            .join()
+        {
+            let position = transform.translation().xy();
+            let max_gap = range.reach.max(range.lunge_distance);
+            // The grid only knows where targets are, not how large they are.
+            let nearby = spatial_grid.query(transform, radius(attacker) + max_gap + MAX_RADIUS);
+            let target = (&entities, &has_faction, &transforms, &nearby)
+                .join()
+                .filter(|(target, target_faction, _, _)| {
+                    *target != attacker
+                        && relations.attacks(faction.faction, target_faction.faction)
+                })
+                .map(|(target, _, target_transform, _)| {
+                    let offset = target_transform.translation().xy() - position;
+                    let gap = offset.magnitude() - radius(attacker) - radius(target);
+                    (target, offset, gap)
+                })
+                .filter(|(_, _, gap)| *gap <= max_gap)
+                .min_by(|(_, _, a), (_, _, b)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
 
-                let preys_b = faction_preys.get(faction_b.faction);
-                if let Some(preys) = preys_b {
-                    if preys.is_prey(&faction_a.faction) {
-                        attack_events.single_write(AttackEvent {
-                            attacker: event.entity_b,
-                            defender: event.entity_a,
-                        });
-                    }
+            match target {
+                Some((defender, _, gap)) if gap <= range.reach => {
+                    attack_events.single_write(AttackEvent { attacker, defender });
                 }
+                Some((_, offset, gap))
+                    if gap <= range.lunge_distance && !lunges.contains(attacker) =>
+                {
+                    new_lunges.push((attacker, offset, range.clone()));
+                }
+                _ => (),
             }
         }
-    }
 
-    fn setup(&mut self, world: &mut World) {
-        <Self as System<'_>>::SystemData::setup(world);
-        self.event_reader = Some(
-            world
-                .fetch_mut::<EventChannel<CollisionEvent>>()
-                .register_reader(),
-        )
+        for (attacker, offset, range) in new_lunges {
+            if let Some(movement) = movements.get_mut(attacker) {
+                let direction = offset
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vector2::zeros);
+                let speed = movement.max_movement_speed * range.lunge_speed;
+                movement.velocity.x = direction.x * speed;
+                movement.velocity.y = direction.y * speed;
+                lunges
+                    .insert(
+                        attacker,
+                        Lunge {
+                            time_left: range.lunge_duration,
+                            speed_multiplier: range.lunge_speed,
+                        },
+                    )
+                    .expect("Unreachable: we are inserting now.");
+            }
+        }
     }
 }
 
@@ -1,12 +1,37 @@
 use amethyst::{core::transform::Transform, ecs::*, shrev::EventChannel};
+use std::collections::HashMap;
 use std::f32;
 
-use crate::components::{combat::Health, creatures::Carcass, digestion::Fullness};
-use crate::systems::spawner::CreatureSpawnEvent;
+use crate::components::{
+    combat::{CarrionTag, HasFaction, Health},
+    creatures::Carcass,
+    digestion::Fullness,
+    photosynthesis::Photosynthesis,
+};
+use crate::resources::{deaths::Deaths, simulation_time::SimulationTime};
+use crate::systems::{combat::AttackResolvedEvent, spawner::CreatureSpawnEvent};
+
+/// Why a creature died.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathCause {
+    Starvation,
+    /// Lost all of its health. The killer is the attacker that dealt the last hit, if any.
+    Predation {
+        killer: Option<Entity>,
+    },
+    /// Eaten up without being hunted: a carcass picked clean or a plant grazed to nothing. The
+    /// eater is the one that took the last bite, if any.
+    Consumed {
+        eater: Option<Entity>,
+    },
+    OldAge,
+    OutOfBounds,
+}
 
 #[derive(Debug, Clone)]
 pub struct CreatureDeathEvent {
     pub deceased: Entity,
+    pub cause: DeathCause,
 }
 
 pub struct StarvationSystem;
@@ -17,32 +42,89 @@ impl<'s> System<'s> for StarvationSystem {
         ReadStorage<'s, Fullness>,
         Entities<'s>,
         Write<'s, EventChannel<CreatureDeathEvent>>,
+        Write<'s, Deaths>,
+        Read<'s, SimulationTime>,
     );
 
-    fn run(&mut self, (fullnesses, entities, mut death_events): Self::SystemData) {
+    fn run(
+        &mut self,
+        (fullnesses, entities, mut death_events, mut deaths, time): Self::SystemData,
+    ) {
         for (fullness, entity) in (&fullnesses, &*entities).join() {
-            if fullness.value < f32::EPSILON {
-                death_events.single_write(CreatureDeathEvent { deceased: entity });
+            if fullness.value < f32::EPSILON && deaths.record(time.ticks(), entity) {
+                death_events.single_write(CreatureDeathEvent {
+                    deceased: entity,
+                    cause: DeathCause::Starvation,
+                });
                 let _ = entities.delete(entity);
             }
         }
     }
 }
 
-pub struct DeathByHealthSystem;
+#[derive(Default)]
+pub struct DeathByHealthSystem {
+    attack_reader_id: Option<ReaderId<AttackResolvedEvent>>,
+}
 
-// Entities die if their health reaches zero (or less).
+// Entities die if their health reaches zero (or less). Carrion and plants are consumed, anything
+// else falls prey to its killer.
 impl<'s> System<'s> for DeathByHealthSystem {
     type SystemData = (
         ReadStorage<'s, Health>,
+        ReadStorage<'s, HasFaction<Entity>>,
+        ReadStorage<'s, CarrionTag>,
+        ReadStorage<'s, Photosynthesis>,
         Entities<'s>,
+        Read<'s, EventChannel<AttackResolvedEvent>>,
         Write<'s, EventChannel<CreatureDeathEvent>>,
+        Write<'s, Deaths>,
+        Read<'s, SimulationTime>,
     );
 
-    fn run(&mut self, (healths, entities, mut death_events): Self::SystemData) {
+    fn setup(&mut self, world: &mut World) {
+        <Self as System<'_>>::SystemData::setup(world);
+        self.attack_reader_id = Some(
+            world
+                .fetch_mut::<EventChannel<AttackResolvedEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(
+        &mut self,
+        (
+            healths,
+            has_faction,
+            carrion_tags,
+            photosyntheses,
+            entities,
+            attack_events,
+            mut death_events,
+            mut deaths,
+            time,
+        ): Self::SystemData,
+    ) {
+        let killers: HashMap<Entity, Entity> = attack_events
+            .read(self.attack_reader_id.as_mut().unwrap())
+            .filter(|event| event.killed)
+            .map(|event| (event.defender, event.attacker))
+            .collect();
         for (health, entity) in (&healths, &*entities).join() {
-            if health.value < f32::EPSILON {
-                death_events.single_write(CreatureDeathEvent { deceased: entity });
+            if health.value < f32::EPSILON && deaths.record(time.ticks(), entity) {
+                let killer = killers.get(&entity).cloned();
+                let carrion = has_faction
+                    .get(entity)
+                    .map_or(false, |faction| carrion_tags.contains(faction.faction));
+                let cause = if carrion || photosyntheses.contains(entity) {
+                    DeathCause::Consumed { eater: killer }
+                } else {
+                    DeathCause::Predation { killer }
+                };
+                death_events.single_write(CreatureDeathEvent {
+                    deceased: entity,
+                    cause,
+                });
                 let _ = entities.delete(entity);
             }
         }
@@ -78,6 +160,10 @@ impl<'s> System<'s> for CarcassSystem {
         (entities, death_events, mut spawn_events, lazy_update, transforms, carcasses): Self::SystemData,
     ) {
         for event in death_events.read(self.death_reader_id.as_mut().unwrap()) {
+            // Creatures that left the world leave nothing behind in it.
+            if event.cause == DeathCause::OutOfBounds {
+                continue;
+            }
             let mut deceased = BitSet::new();
             deceased.add(event.deceased.id());
 
@@ -87,6 +173,7 @@ impl<'s> System<'s> for CarcassSystem {
                 spawn_events.single_write(CreatureSpawnEvent {
                     creature_type: carcass.creature_type.clone(),
                     entity: creature_entity_builder.build(),
+                    parent: None,
                 });
             }
         }
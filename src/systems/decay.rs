@@ -0,0 +1,75 @@
+use amethyst::{
+    core::transform::Transform,
+    ecs::*,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::{
+    components::{decay::Decay, digestion::Nutrition},
+    resources::{simulation_time::SimulationTime, soil::SoilFertility},
+    systems::death::CreatureDeathEvent,
+};
+
+/// Rots every carcass with `Decay` into the soil below it. A carcass is gone once its nutrition
+/// has rotted or been eaten away. A carcass that is destroyed before that leaves its remaining
+/// nutrition in the soil.
+#[derive(Default)]
+pub struct DecaySystem {
+    death_reader_id: Option<ReaderId<CreatureDeathEvent>>,
+}
+
+impl<'s> System<'s> for DecaySystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Decay>,
+        WriteStorage<'s, Nutrition>,
+        WriteStorage<'s, Transform>,
+        Read<'s, EventChannel<CreatureDeathEvent>>,
+        WriteExpect<'s, SoilFertility>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        <Self as System<'_>>::SystemData::setup(world);
+        self.death_reader_id = Some(
+            world
+                .fetch_mut::<EventChannel<CreatureDeathEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(
+        &mut self,
+        (entities, mut decays, mut nutritions, mut transforms, death_events, mut soil, time): Self::SystemData,
+    ) {
+        for event in death_events.read(self.death_reader_id.as_mut().unwrap()) {
+            if let (Some(_), Some(nutrition), Some(transform)) = (
+                decays.get(event.deceased),
+                nutritions.get_mut(event.deceased),
+                transforms.get(event.deceased),
+            ) {
+                let translation = transform.translation();
+                soil.deposit(translation.x, translation.y, nutrition.value);
+                nutrition.value = 0.0;
+            }
+        }
+
+        for (entity, decay, nutrition, transform) in
+            (&entities, &mut decays, &mut nutritions, &mut transforms).join()
+        {
+            decay.initial_nutrition.get_or_insert(nutrition.value);
+            decay.initial_scale.get_or_insert(*transform.scale());
+
+            let rotted = (decay.rot_rate * time.delta_seconds()).min(nutrition.value);
+            nutrition.value -= rotted;
+            let translation = transform.translation();
+            soil.deposit(translation.x, translation.y, rotted);
+
+            if nutrition.value < f32::EPSILON {
+                let _ = entities.delete(entity);
+            } else if let Some(scale) = decay.scale(nutrition.value) {
+                transform.set_scale(scale);
+            }
+        }
+    }
+}
@@ -1,7 +1,13 @@
+#[cfg(feature = "render")]
+use amethyst::core::Transform;
+use amethyst::ecs::*;
+#[cfg(feature = "render")]
 use amethyst::renderer::{debug_drawing::DebugLines, palette::Srgba};
-use amethyst::{core::Time, core::Transform, ecs::*};
 
-use crate::components::digestion::{Digestion, Fullness};
+use crate::{
+    components::digestion::{Digestion, Fullness},
+    resources::simulation_time::SimulationTime,
+};
 
 pub struct DigestionSystem;
 
@@ -9,7 +15,7 @@ impl<'s> System<'s> for DigestionSystem {
     type SystemData = (
         ReadStorage<'s, Digestion>,
         WriteStorage<'s, Fullness>,
-        Read<'s, Time>,
+        Read<'s, SimulationTime>,
     );
 
     fn run(&mut self, (digestions, mut fullnesses, time): Self::SystemData) {
@@ -22,8 +28,10 @@ impl<'s> System<'s> for DigestionSystem {
     }
 }
 
+#[cfg(feature = "render")]
 pub struct DebugFullnessSystem;
 
+#[cfg(feature = "render")]
 impl<'s> System<'s> for DebugFullnessSystem {
     type SystemData = (
         ReadStorage<'s, Fullness>,
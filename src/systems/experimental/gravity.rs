@@ -1,6 +1,9 @@
-use amethyst::{core::timing::Time, ecs::*};
+use amethyst::ecs::*;
 
-use crate::{components::creatures::FallingTag, components::creatures::Movement};
+use crate::{
+    components::creatures::FallingTag, components::creatures::Movement,
+    resources::simulation_time::SimulationTime,
+};
 
 /// Acceleration due to gravity.
 const GRAVITY: f32 = 4.0;
@@ -14,7 +17,7 @@ impl<'s> System<'s> for GravitySystem {
     type SystemData = (
         WriteStorage<'s, Movement>,
         ReadStorage<'s, FallingTag>,
-        Read<'s, Time>,
+        Read<'s, SimulationTime>,
     );
 
     fn run(&mut self, (mut movements, falling_tags, time): Self::SystemData) {
@@ -1,10 +1,13 @@
-use crate::resources::world_bounds::WorldBounds;
-use amethyst::{core::transform::components::Transform, ecs::*};
+use crate::resources::{
+    deaths::Deaths, simulation_time::SimulationTime, world_bounds::WorldBounds,
+};
+use amethyst::{core::transform::components::Transform, ecs::*, shrev::EventChannel};
 
-use crate::components::creatures::DespawnWhenOutOfBoundsTag;
+use crate::components::creatures::{CreatureTag, DespawnWhenOutOfBoundsTag};
+use crate::systems::death::{CreatureDeathEvent, DeathCause};
 
 /// Deletes any entity tagged with DespawnWhenOutOfBoundsTag if they are detected to be outside
-/// the world bounds.
+/// the world bounds. Creatures that leave the world die.
 #[derive(Default)]
 pub struct OutOfBoundsDespawnSystem;
 
@@ -13,10 +16,26 @@ impl<'s> System<'s> for OutOfBoundsDespawnSystem {
         Entities<'s>,
         ReadStorage<'s, Transform>,
         ReadStorage<'s, DespawnWhenOutOfBoundsTag>,
+        ReadStorage<'s, CreatureTag>,
         ReadExpect<'s, WorldBounds>,
+        Write<'s, EventChannel<CreatureDeathEvent>>,
+        Write<'s, Deaths>,
+        Read<'s, SimulationTime>,
     );
 
-    fn run(&mut self, (entities, locals, tags, bounds): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            entities,
+            locals,
+            tags,
+            creature_tags,
+            bounds,
+            mut death_events,
+            mut deaths,
+            time,
+        ): Self::SystemData,
+    ) {
         for (entity, local, _) in (&*entities, &locals, &tags).join() {
             let pos = local.translation();
             if pos.x > bounds.right
@@ -24,6 +43,12 @@ impl<'s> System<'s> for OutOfBoundsDespawnSystem {
                 || pos.y > bounds.top
                 || pos.y < bounds.bottom
             {
+                if creature_tags.contains(entity) && deaths.record(time.ticks(), entity) {
+                    death_events.single_write(CreatureDeathEvent {
+                        deceased: entity,
+                        cause: DeathCause::OutOfBounds,
+                    });
+                }
                 let _ = entities.delete(entity);
             }
         }
@@ -1,15 +1,25 @@
+#[cfg(feature = "render")]
 use amethyst::{
-    core::{math::Point3, transform::Transform},
-    ecs::{BitSet, Entities, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage},
+    core::math::Point3,
     renderer::{debug_drawing::DebugLinesComponent, palette::Srgba},
 };
+use amethyst::{
+    core::{
+        math::{Vector2, Vector3},
+        transform::Transform,
+    },
+    ecs::{BitSet, Entities, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage},
+};
 
 use crate::components::{
-    creatures::CreatureTag,
-    perception::{DetectedEntities, Perception},
+    creatures::{CreatureTag, Movement},
+    perception::{DetectedEntities, Occluder, Perception},
 };
 use crate::resources::spatial_grid::SpatialGrid;
 
+/// Detects what every creature with `Perception` can see: whatever is in range and in its view
+/// cone, unless an `Occluder` is in the way, and whatever is close enough to notice regardless.
+/// Creatures look where they are moving, or where they are facing when they stand still.
 pub struct EntityDetectionSystem;
 
 impl<'s> System<'s> for EntityDetectionSystem {
@@ -19,11 +29,13 @@ impl<'s> System<'s> for EntityDetectionSystem {
         WriteStorage<'s, DetectedEntities>,
         ReadExpect<'s, SpatialGrid>,
         ReadStorage<'s, Transform>,
+        ReadStorage<'s, Movement>,
+        ReadStorage<'s, Occluder>,
     );
 
     fn run(
         &mut self,
-        (entities, perceptions, mut detected_entities, grid, transforms): Self::SystemData,
+        (entities, perceptions, mut detected_entities, grid, transforms, movements, occluders): Self::SystemData,
     ) {
         for (entity, _) in (&entities, &perceptions).join() {
             match detected_entities.get(entity) {
@@ -36,18 +48,40 @@ impl<'s> System<'s> for EntityDetectionSystem {
             }
         }
 
-        for (perception, mut detected, transform) in
-            (&perceptions, &mut detected_entities, &transforms).join()
+        for (entity, perception, mut detected, transform) in
+            (&entities, &perceptions, &mut detected_entities, &transforms).join()
         {
             detected.entities = BitSet::new();
             let nearby_entities = grid.query(transform, perception.range);
-            let pos = transform.global_matrix().column(3).xyz();
-            let sq_range = perception.range * perception.range;
+            let pos = transform.global_matrix().column(3).xy();
+            let heading = match movements.get(entity) {
+                Some(movement) if movement.velocity.xy().norm() > std::f32::EPSILON => {
+                    movement.velocity.xy()
+                }
+                _ => (transform.rotation() * Vector3::x()).xy(),
+            };
+            let in_the_way = (&entities, &occluders, &transforms, &nearby_entities)
+                .join()
+                .filter(|(occluding_entity, _, _, _)| *occluding_entity != entity)
+                .map(|(occluding_entity, occluder, occluder_transform, _)| {
+                    let offset: Vector2<f32> =
+                        occluder_transform.global_matrix().column(3).xy() - pos;
+                    (occluding_entity, occluder, offset)
+                })
+                .collect::<Vec<_>>();
             for (other_entity, other_transform, _) in
                 (&entities, &transforms, &nearby_entities).join()
             {
-                let other_pos = other_transform.global_matrix().column(3).xyz();
-                if (pos - other_pos).norm_squared() < sq_range {
+                let offset = other_transform.global_matrix().column(3).xy() - pos;
+                let seen = perception.senses(&offset)
+                    || (perception.in_view(&offset, &heading)
+                        && !in_the_way.iter().any(
+                            |(occluding_entity, occluder, occluder_offset)| {
+                                *occluding_entity != other_entity
+                                    && occluder.blocks(occluder_offset, &offset)
+                            },
+                        ));
+                if seen {
                     detected.entities.add(other_entity.id());
                 }
             }
@@ -73,8 +107,10 @@ impl<'s> System<'s> for SpatialGridSystem {
     }
 }
 
+#[cfg(feature = "render")]
 pub struct DebugEntityDetectionSystem;
 
+#[cfg(feature = "render")]
 impl<'s> System<'s> for DebugEntityDetectionSystem {
     type SystemData = (
         ReadStorage<'s, DetectedEntities>,
@@ -1,15 +1,14 @@
-use crate::resources::world_bounds::WorldBounds;
+use crate::resources::{rng::SimRng, simulation_time::SimulationTime, world_bounds::WorldBounds};
 use amethyst::{
     core::{
         math::{Vector2, Vector3},
-        timing::Time,
         transform::components::Transform,
     },
     ecs::*,
     shrev::EventChannel,
 };
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use std::f32;
 
 use crate::{
@@ -49,14 +48,15 @@ impl<'s> System<'s> for TopplegrassSpawnSystem {
         Entities<'s>,
         Read<'s, LazyUpdate>,
         Write<'s, EventChannel<CreatureSpawnEvent>>,
-        Read<'s, Time>,
+        Read<'s, SimulationTime>,
         Read<'s, WorldBounds>,
         Read<'s, Wind>,
+        WriteExpect<'s, SimRng>,
     );
 
     fn run(
         &mut self,
-        (entities, lazy_update, mut spawn_events, time, world_bounds, wind): Self::SystemData,
+        (entities, lazy_update, mut spawn_events, time, world_bounds, wind, mut sim_rng): Self::SystemData,
     ) {
         if self.ready_to_spawn(time.delta_seconds()) {
             let mut transform = Transform::default();
@@ -65,11 +65,16 @@ impl<'s> System<'s> for TopplegrassSpawnSystem {
                 TOPPLEGRASS_BASE_SCALE,
                 TOPPLEGRASS_BASE_SCALE,
             ));
-            transform.append_translation(Self::gen_spawn_location(&wind, &world_bounds));
+            transform.append_translation(Self::gen_spawn_location(
+                &wind,
+                &world_bounds,
+                sim_rng.stream("topplegrass"),
+            ));
             let entity = lazy_update.create_entity(&entities).with(transform).build();
             spawn_events.single_write(CreatureSpawnEvent {
                 creature_type: "Topplegrass".to_string(),
                 entity,
+                parent: None,
             });
         }
     }
@@ -92,8 +97,7 @@ impl TopplegrassSpawnSystem {
     /// Entities will be spawned at a random point on one of the four world borders; specifically,
     /// the one that the wind direction is facing away from. In other words: upwind from the
     /// center of the world.
-    fn gen_spawn_location(wind: &Wind, bounds: &WorldBounds) -> Vector3<f32> {
-        let mut rng = thread_rng();
+    fn gen_spawn_location<R: Rng>(wind: &Wind, bounds: &WorldBounds, rng: &mut R) -> Vector3<f32> {
         if Self::wind_towards_direction(wind.wind, Vector2::new(1.0, 0.0)) {
             Vector3::new(
                 bounds.left,
@@ -138,14 +142,24 @@ impl<'s> System<'s> for TopplingSystem {
         ReadStorage<'s, TopplegrassTag>,
         WriteStorage<'s, FallingTag>,
         Read<'s, Wind>,
-        Read<'s, Time>,
+        Read<'s, SimulationTime>,
+        WriteExpect<'s, SimRng>,
     );
 
     fn run(
         &mut self,
-        (entities, mut movements, mut transforms, topple_tags, mut falling_tags, wind, time): Self::SystemData,
+        (
+            entities,
+            mut movements,
+            mut transforms,
+            topple_tags,
+            mut falling_tags,
+            wind,
+            time,
+            mut sim_rng,
+        ): Self::SystemData,
     ) {
-        let mut rng = thread_rng();
+        let rng = sim_rng.stream("toppling");
         // Set topplegrass velocity to equal wind velocity.
         // Rotate topplegrass.
         for (movement, transform, _) in (&mut movements, &mut transforms, &topple_tags).join() {
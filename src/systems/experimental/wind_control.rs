@@ -1,5 +1,5 @@
 use amethyst::{
-    core::{math::Vector2, timing::Time},
+    core::{math::Vector2, Time},
     ecs::*,
     input::{InputHandler, StringBindings},
 };
@@ -19,6 +19,8 @@ const WIND_ACCELERATION: f32 = 2.0;
 /// DebugWindControlSystem allows players to change the wind speed and direction at runtime.
 /// Use the ChangeWindDirection input axis to change the wind direction at WIND_TURN_SPEED radians per second.
 /// Use the ChangeWindSpeed input axis to change the wind speed between MIN_WIND_SPEED and MAX_WIND_SPEED.
+/// It runs once per frame and follows the real frame time, so the wind can be changed while the
+/// simulation is paused or running at a different speed.
 #[derive(Default)]
 pub struct DebugWindControlSystem;
 
@@ -52,7 +54,7 @@ impl<'s> System<'s> for DebugWindControlSystem {
 fn calc_wind_angle(input_signum: Option<f32>, wind: &Wind, time: &Time) -> f32 {
     let old_wind_angle = wind.wind.y.atan2(wind.wind.x);
     if let Some(signum) = input_signum {
-        old_wind_angle + signum * WIND_TURN_SPEED * time.delta_seconds()
+        old_wind_angle + signum * WIND_TURN_SPEED * time.delta_real_seconds()
     } else {
         old_wind_angle
     }
@@ -61,7 +63,7 @@ fn calc_wind_angle(input_signum: Option<f32>, wind: &Wind, time: &Time) -> f32 {
 fn calc_wind_speed(input_signum: Option<f32>, wind: &Wind, time: &Time) -> f32 {
     let magnitude = wind.wind.magnitude();
     if let Some(signum) = input_signum {
-        (magnitude + signum * WIND_ACCELERATION * time.delta_seconds())
+        (magnitude + signum * WIND_ACCELERATION * time.delta_real_seconds())
             .max(MIN_WIND_SPEED)
             .min(MAX_WIND_SPEED)
     } else {
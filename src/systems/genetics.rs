@@ -0,0 +1,133 @@
+use amethyst::ecs::*;
+
+use crate::{
+    components::{
+        collider::Circle,
+        combat::{Damage, Health},
+        creatures::Movement,
+        digestion::Digestion,
+        genetics::{Gene, Genetics, Genome, InheritedGenome},
+        perception::Perception,
+    },
+    resources::rng::SimRng,
+    utils::save_game::PendingRestore,
+};
+
+/// Gives every creature with `Genetics` its `Genome` once its prefab has been instantiated, and
+/// expresses the genome in the components of the creature.
+/// Newborns inherit a mutated copy of their parent's genome, see `InheritedGenome`. Creatures
+/// without a parent take the values of their prefab.
+pub struct GenomeSystem;
+
+impl<'s> System<'s> for GenomeSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Genetics>,
+        WriteStorage<'s, Genome>,
+        WriteStorage<'s, InheritedGenome>,
+        ReadStorage<'s, PendingRestore>,
+        WriteStorage<'s, Movement>,
+        WriteStorage<'s, Perception>,
+        WriteStorage<'s, Circle>,
+        WriteStorage<'s, Damage>,
+        WriteStorage<'s, Digestion>,
+        WriteStorage<'s, Health>,
+        WriteExpect<'s, SimRng>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            genetics,
+            mut genomes,
+            mut inherited_genomes,
+            pending_restores,
+            mut movements,
+            mut perceptions,
+            mut circles,
+            mut damages,
+            mut digestions,
+            mut healths,
+            mut sim_rng,
+        ): Self::SystemData,
+    ) {
+        // Loaded creatures get their genome back from the save game.
+        let newborns = (&entities, &genetics, !&genomes, !&pending_restores)
+            .join()
+            .map(|(entity, _, _, _)| entity)
+            .collect::<Vec<Entity>>();
+        let rng = sim_rng.stream("genetics");
+
+        for entity in newborns {
+            let creature_genetics = genetics
+                .get(entity)
+                .expect("unreachable, joined just before");
+            let genome = match inherited_genomes.remove(entity) {
+                Some(InheritedGenome(parent)) => creature_genetics.inherit(&parent, rng),
+                None => Genome {
+                    genes: creature_genetics
+                        .genes
+                        .keys()
+                        .filter_map(|gene| {
+                            let value = match gene {
+                                Gene::MaxMovementSpeed => {
+                                    movements.get(entity).map(|m| m.max_movement_speed)
+                                }
+                                Gene::PerceptionRange => perceptions.get(entity).map(|p| p.range),
+                                Gene::ColliderRadius => circles.get(entity).map(|c| c.radius),
+                                Gene::Damage => damages.get(entity).map(|d| d.damage),
+                                Gene::NutritionBurnRate => {
+                                    digestions.get(entity).map(|d| d.nutrition_burn_rate)
+                                }
+                                Gene::MaxHealth => healths.get(entity).map(|h| h.max_health),
+                            };
+                            value.map(|value| (*gene, value))
+                        })
+                        .collect(),
+                },
+            };
+
+            for (gene, value) in &genome.genes {
+                let value = *value;
+                match gene {
+                    Gene::MaxMovementSpeed => {
+                        if let Some(movement) = movements.get_mut(entity) {
+                            movement.max_movement_speed = value;
+                        }
+                    }
+                    Gene::PerceptionRange => {
+                        if let Some(perception) = perceptions.get_mut(entity) {
+                            perception.range = value;
+                        }
+                    }
+                    Gene::ColliderRadius => {
+                        if let Some(circle) = circles.get_mut(entity) {
+                            circle.radius = value;
+                        }
+                    }
+                    Gene::Damage => {
+                        if let Some(damage) = damages.get_mut(entity) {
+                            damage.damage = value;
+                        }
+                    }
+                    Gene::NutritionBurnRate => {
+                        if let Some(digestion) = digestions.get_mut(entity) {
+                            digestion.nutrition_burn_rate = value;
+                        }
+                    }
+                    Gene::MaxHealth => {
+                        if let Some(health) = healths.get_mut(entity) {
+                            health.max_health = value;
+                            health.value = value;
+                        }
+                    }
+                }
+            }
+
+            genomes
+                .insert(entity, genome)
+                .expect("unreachable, joined just before");
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use amethyst::{core::transform::Transform, ecs::*};
+
+use crate::{
+    components::{creatures::Movement, interpolation::InterpolatedTranslation},
+    resources::simulation_time::SimulationTime,
+};
+
+/// Records the translation of every moving entity at the end of a simulation tick.
+pub struct CaptureTranslationSystem;
+impl<'s> System<'s> for CaptureTranslationSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Movement>,
+        ReadStorage<'s, Transform>,
+        WriteStorage<'s, InterpolatedTranslation>,
+    );
+
+    fn run(&mut self, (entities, movements, transforms, mut interpolated): Self::SystemData) {
+        for (entity, _, transform) in (&entities, &movements, &transforms).join() {
+            let translation = *transform.translation();
+            if let Some(interpolated) = interpolated.get_mut(entity) {
+                interpolated.previous = interpolated.current;
+                interpolated.current = translation;
+            } else {
+                interpolated
+                    .insert(entity, InterpolatedTranslation::new(translation))
+                    .expect("unreachable, the entity has been joined just before");
+            }
+        }
+    }
+}
+
+/// Puts back the translation of the last simulation tick before the simulation runs again, so
+/// the systems never see the blended translation that was rendered.
+pub struct RestoreTranslationSystem;
+impl<'s> System<'s> for RestoreTranslationSystem {
+    type SystemData = (
+        ReadStorage<'s, InterpolatedTranslation>,
+        WriteStorage<'s, Transform>,
+    );
+
+    fn run(&mut self, (interpolated, mut transforms): Self::SystemData) {
+        for (interpolated, transform) in (&interpolated, &mut transforms).join() {
+            transform.set_translation(interpolated.current);
+        }
+    }
+}
+
+/// Blends the translation of moving entities between the last two simulation ticks, according
+/// to how much frame time is left over in the `SimulationTime` accumulator.
+pub struct InterpolateTranslationSystem;
+impl<'s> System<'s> for InterpolateTranslationSystem {
+    type SystemData = (
+        ReadStorage<'s, InterpolatedTranslation>,
+        WriteStorage<'s, Transform>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn run(&mut self, (interpolated, mut transforms, time): Self::SystemData) {
+        let alpha = time.interpolation_alpha();
+        for (interpolated, transform) in (&interpolated, &mut transforms).join() {
+            transform.set_translation(interpolated.previous.lerp(&interpolated.current, alpha));
+        }
+    }
+}
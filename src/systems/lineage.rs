@@ -0,0 +1,77 @@
+use amethyst::{
+    core::Named,
+    ecs::*,
+    shrev::{EventChannel, ReaderId},
+};
+
+use crate::{
+    components::{
+        genetics::Genome,
+        lineage::{LineageId, LineageParent},
+    },
+    resources::{lineage::LineageRegistry, simulation_time::SimulationTime},
+    systems::death::CreatureDeathEvent,
+};
+
+/// Registers the birth of every creature once it has its `Genome`, and the death of every
+/// registered creature, in the `LineageRegistry`.
+#[derive(Default)]
+pub struct LineageSystem {
+    death_reader_id: Option<ReaderId<CreatureDeathEvent>>,
+}
+
+impl<'s> System<'s> for LineageSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Genome>,
+        ReadStorage<'s, Named>,
+        WriteStorage<'s, LineageId>,
+        WriteStorage<'s, LineageParent>,
+        Read<'s, EventChannel<CreatureDeathEvent>>,
+        Write<'s, LineageRegistry>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        <Self as System<'_>>::SystemData::setup(world);
+        self.death_reader_id = Some(
+            world
+                .fetch_mut::<EventChannel<CreatureDeathEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            genomes,
+            names,
+            mut lineage_ids,
+            mut lineage_parents,
+            death_events,
+            mut registry,
+            time,
+        ): Self::SystemData,
+    ) {
+        let now = time.elapsed_seconds();
+
+        let newborns = (&entities, &genomes, &names, !&lineage_ids)
+            .join()
+            .map(|(entity, genome, name, _)| (entity, genome.clone(), name.name.to_string()))
+            .collect::<Vec<_>>();
+        for (entity, genome, creature_type) in newborns {
+            let parent = lineage_parents.remove(entity).map(|parent| parent.0);
+            let id = registry.register_birth(parent, creature_type, now, genome);
+            lineage_ids
+                .insert(entity, LineageId(id))
+                .expect("unreachable, joined just before");
+        }
+
+        for event in death_events.read(self.death_reader_id.as_mut().unwrap()) {
+            if let Some(lineage_id) = lineage_ids.get(event.deceased) {
+                registry.register_death(lineage_id.0, now);
+            }
+        }
+    }
+}
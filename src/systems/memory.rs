@@ -0,0 +1,151 @@
+#[cfg(feature = "render")]
+use amethyst::{
+    core::math::Point3,
+    renderer::{debug_drawing::DebugLinesComponent, palette::Srgba},
+};
+use amethyst::{
+    core::{math::Vector3, transform::Transform},
+    ecs::*,
+};
+
+use crate::{
+    components::memory::{Memory, MemoryKind},
+    resources::simulation_time::SimulationTime,
+    systems::behaviors::decision::{Carrion, Closest, Predator, Prey},
+};
+
+/// Updates what every creature with `Memory` remembers. The closest prey, predator and carrion
+/// it perceives are remembered where they are; where it was born is remembered as its home.
+/// Memories fade after a while, and a creature that reaches a remembered place and finds nothing
+/// there forgets it.
+///
+/// While a creature perceives nothing of a kind, the closest place it remembers becomes its
+/// `Closest<T>`, so the decision systems after this one lead it back to remembered food and away
+/// from remembered danger.
+pub struct MemorySystem;
+
+impl<'s> System<'s> for MemorySystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Memory>,
+        ReadStorage<'s, Transform>,
+        WriteStorage<'s, Closest<Prey>>,
+        WriteStorage<'s, Closest<Predator>>,
+        WriteStorage<'s, Closest<Carrion>>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut memories,
+            transforms,
+            mut closest_prey,
+            mut closest_predators,
+            mut closest_carrion,
+            time,
+        ): Self::SystemData,
+    ) {
+        let now = time.elapsed_seconds();
+        for (entity, memory, transform) in (&entities, &mut memories, &transforms).join() {
+            let position = *transform.translation();
+            if memory.recall(MemoryKind::Home, &position).is_none() {
+                memory.remember(MemoryKind::Home, position, now);
+            }
+            memory.fade(now);
+
+            update(
+                entity,
+                MemoryKind::Prey,
+                memory,
+                &position,
+                now,
+                &mut closest_prey,
+            );
+            update(
+                entity,
+                MemoryKind::Predator,
+                memory,
+                &position,
+                now,
+                &mut closest_predators,
+            );
+            update(
+                entity,
+                MemoryKind::Carrion,
+                memory,
+                &position,
+                now,
+                &mut closest_carrion,
+            );
+        }
+    }
+}
+
+/// Remembers the `Closest<T>` of `entity` as `kind`, or puts back the closest place of that kind
+/// it remembers if it perceives none.
+fn update<T>(
+    entity: Entity,
+    kind: MemoryKind,
+    memory: &mut Memory,
+    position: &Vector3<f32>,
+    now: f64,
+    closest: &mut WriteStorage<Closest<T>>,
+) where
+    T: shred::Resource + Default,
+{
+    if let Some(seen) = closest.get(entity) {
+        memory.remember(kind, position + seen.distance, now);
+        return;
+    }
+
+    // Whatever the creature remembers right where it is, is not there anymore.
+    let merge_radius = memory.merge_radius;
+    memory.forget_around(kind, position, merge_radius);
+
+    if let Some(remembered) = memory.recall(kind, position) {
+        closest
+            .insert(entity, Closest::new(remembered.position - position))
+            .expect("unreachable: the entity is alive");
+    }
+}
+
+/// Draws a line from every creature with `Memory` to each place it remembers, fading with the
+/// memory.
+#[cfg(feature = "render")]
+pub struct DebugMemorySystem;
+
+#[cfg(feature = "render")]
+impl<'s> System<'s> for DebugMemorySystem {
+    type SystemData = (
+        ReadStorage<'s, Memory>,
+        ReadStorage<'s, Transform>,
+        Read<'s, SimulationTime>,
+        WriteStorage<'s, DebugLinesComponent>,
+    );
+
+    fn run(&mut self, (memories, transforms, time, mut debug_lines_comps): Self::SystemData) {
+        let now = time.elapsed_seconds();
+        for (memory, transform, debug_comp) in
+            (&memories, &transforms, &mut debug_lines_comps).join()
+        {
+            let mut pos = transform.global_matrix().column(3).xyz();
+            pos[2] += 0.4;
+            for remembered in memory.memories.iter() {
+                let strength = memory.strength(remembered, now);
+                let (red, green, blue) = match remembered.kind {
+                    MemoryKind::Prey => (0.1, 0.9, 0.1),
+                    MemoryKind::Predator => (0.9, 0.1, 0.1),
+                    MemoryKind::Carrion => (0.6, 0.4, 0.2),
+                    MemoryKind::Home => (0.2, 0.4, 1.0),
+                };
+                let color = Srgba::new(red, green, blue, strength);
+                let mut remembered_pos = remembered.position;
+                remembered_pos[2] = pos[2];
+                debug_comp.add_line(Point3::from(pos), Point3::from(remembered_pos), color);
+                debug_comp.add_circle_2d(Point3::from(remembered_pos), 0.2, 8, color);
+            }
+        }
+    }
+}
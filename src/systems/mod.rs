@@ -1,14 +1,30 @@
+pub mod aging;
 pub mod behaviors;
+#[cfg(feature = "render")]
 pub mod camera_movement;
 pub mod collision;
 pub mod combat;
 pub mod death;
+#[cfg(feature = "render")]
 pub mod debug;
+pub mod decay;
 pub mod digestion;
+pub mod genetics;
+#[cfg(feature = "render")]
 pub mod health;
+pub mod interpolation;
+pub mod lineage;
+#[cfg(feature = "render")]
 pub mod main_game_ui;
+pub mod memory;
 pub mod movement;
+pub mod photosynthesis;
+pub mod regeneration;
+pub mod reproduction;
+pub mod restore;
+pub mod seeds;
 pub mod spawner;
+pub mod statistics;
 pub mod swarm_behavior;
 
 mod experimental;
@@ -1,6 +1,12 @@
-use amethyst::{core::transform::Transform, core::Time, ecs::*};
+use amethyst::{core::transform::Transform, ecs::*};
 
-use crate::components::creatures::{CreatureTag, Movement};
+use crate::{
+    components::{
+        combat::Lunge,
+        creatures::{CreatureTag, Movement},
+    },
+    resources::simulation_time::SimulationTime,
+};
 
 pub struct MovementSystem;
 impl<'s> System<'s> for MovementSystem {
@@ -8,15 +14,23 @@ impl<'s> System<'s> for MovementSystem {
         WriteStorage<'s, Movement>,
         WriteStorage<'s, Transform>,
         ReadStorage<'s, CreatureTag>,
-        Read<'s, Time>,
+        ReadStorage<'s, Lunge>,
+        Read<'s, SimulationTime>,
     );
 
-    fn run(&mut self, (mut movements, mut transforms, creature_tags, time): Self::SystemData) {
+    fn run(
+        &mut self,
+        (mut movements, mut transforms, creature_tags, lunges, time): Self::SystemData,
+    ) {
         let delta_time = time.delta_seconds();
-        for (movement, transform) in (&mut movements, &mut transforms).join() {
+        for (movement, transform, lunge) in (&mut movements, &mut transforms, lunges.maybe()).join()
+        {
+            // Lunging creatures may briefly exceed their top speed.
+            let max_speed = movement.max_movement_speed
+                * lunge.map_or(1.0, |lunge| lunge.speed_multiplier.max(1.0));
             let magnitude = movement.velocity.magnitude();
-            if magnitude > movement.max_movement_speed {
-                movement.velocity = movement.velocity * (movement.max_movement_speed / magnitude);
+            if magnitude > max_speed {
+                movement.velocity = movement.velocity * (max_speed / magnitude);
             }
             transform.prepend_translation_x(movement.velocity.x * delta_time);
             transform.prepend_translation_y(movement.velocity.y * delta_time);
@@ -0,0 +1,119 @@
+#[cfg(feature = "render")]
+use amethyst::renderer::light::Light;
+use amethyst::{
+    core::{math::Vector3, Transform},
+    ecs::*,
+};
+
+use crate::{
+    components::{
+        combat::Health,
+        digestion::{Fullness, Nutrition},
+        photosynthesis::Photosynthesis,
+    },
+    resources::{simulation_time::SimulationTime, soil::SoilFertility, sunlight::Sunlight},
+};
+
+/// Share of its maximum fullness a plant keeps to itself instead of spending it on regrowth, so
+/// grazing alone does not starve it.
+const REGROWTH_RESERVE: f32 = 0.5;
+
+/// Turns sunlight into fullness, spends the fullness on regrowing nutrition and health, and
+/// scales every plant with its nutrition. Plants on fertile soil take up its nutrients to regrow
+/// faster, see `SoilFertility::boost`.
+pub struct PhotosynthesisSystem;
+
+impl<'s> System<'s> for PhotosynthesisSystem {
+    type SystemData = (
+        ReadStorage<'s, Photosynthesis>,
+        WriteStorage<'s, Fullness>,
+        WriteStorage<'s, Nutrition>,
+        WriteStorage<'s, Health>,
+        WriteStorage<'s, Transform>,
+        WriteExpect<'s, SoilFertility>,
+        ReadExpect<'s, Sunlight>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            photosyntheses,
+            mut fullnesses,
+            mut nutritions,
+            mut healths,
+            mut transforms,
+            mut soil,
+            sunlight,
+            time,
+        ): Self::SystemData,
+    ) {
+        let delta_time = time.delta_seconds();
+        let light_level = sunlight.light_level(time.elapsed_seconds());
+
+        for (photosynthesis, fullness, nutrition, health, transform) in (
+            &photosyntheses,
+            &mut fullnesses,
+            (&mut nutritions).maybe(),
+            (&mut healths).maybe(),
+            (&mut transforms).maybe(),
+        )
+            .join()
+        {
+            fullness.value +=
+                (photosynthesis.efficiency * light_level - photosynthesis.upkeep) * delta_time;
+            let mut regrowth = photosynthesis.regrowth_rate * delta_time;
+            if let Some(transform) = &transform {
+                let (x, y) = (transform.translation().x, transform.translation().y);
+                let absorbed = soil.take(x, y, regrowth * (soil.boost(x, y) - 1.0));
+                fullness.value += absorbed;
+                regrowth += absorbed;
+            }
+            fullness.value = fullness.value.min(fullness.max);
+
+            let mut budget = regrowth
+                .min(fullness.value - fullness.max * REGROWTH_RESERVE)
+                .max(0.0);
+            if let Some(nutrition) = nutrition {
+                let regrown = budget
+                    .min(photosynthesis.max_nutrition - nutrition.value)
+                    .max(0.0);
+                nutrition.value += regrown;
+                fullness.value -= regrown;
+                budget -= regrown;
+
+                if let Some(transform) = transform {
+                    let scale = photosynthesis.scale(nutrition.value);
+                    transform.set_scale(Vector3::new(scale, scale, scale));
+                }
+            }
+            if let Some(health) = health {
+                let regrown = budget.min(health.max_health - health.value).max(0.0);
+                health.value += regrown;
+                fullness.value -= regrown;
+            }
+        }
+    }
+}
+
+/// Dims and brightens the rendered sun with the time of day of the simulation.
+#[cfg(feature = "render")]
+pub struct SunlightSystem;
+
+#[cfg(feature = "render")]
+impl<'s> System<'s> for SunlightSystem {
+    type SystemData = (
+        WriteStorage<'s, Light>,
+        ReadExpect<'s, Sunlight>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn run(&mut self, (mut lights, sunlight, time): Self::SystemData) {
+        let daylight = sunlight.daylight(time.elapsed_seconds());
+        for light in (&mut lights).join() {
+            if let Light::Directional(ref mut sun) = light {
+                sun.intensity = sunlight.intensity * daylight;
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use amethyst::ecs::*;
+
+use crate::{
+    components::{combat::Health, digestion::Fullness, regeneration::Regeneration},
+    resources::simulation_time::SimulationTime,
+};
+
+/// Heals creatures with `Regeneration` at the cost of their fullness, and keeps the health and
+/// fullness of every creature within their maximum.
+pub struct RegenerationSystem;
+
+impl<'s> System<'s> for RegenerationSystem {
+    type SystemData = (
+        ReadStorage<'s, Regeneration>,
+        WriteStorage<'s, Health>,
+        WriteStorage<'s, Fullness>,
+        Read<'s, SimulationTime>,
+    );
+
+    fn run(&mut self, (regenerations, mut healths, mut fullnesses, time): Self::SystemData) {
+        for (regeneration, health, fullness) in
+            (&regenerations, &mut healths, &mut fullnesses).join()
+        {
+            // The dead stay dead.
+            if health.value < std::f32::EPSILON {
+                continue;
+            }
+            let healed = regeneration.healed(health, fullness, time.delta_seconds());
+            health.value += healed;
+            fullness.value -= healed * regeneration.fullness_per_health;
+        }
+
+        for health in (&mut healths).join() {
+            health.value = health.value.min(health.max_health);
+        }
+        for fullness in (&mut fullnesses).join() {
+            fullness.value = fullness.value.min(fullness.max);
+        }
+    }
+}
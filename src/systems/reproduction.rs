@@ -0,0 +1,116 @@
+use amethyst::{
+    core::{transform::Transform, Named},
+    ecs::*,
+    shrev::EventChannel,
+};
+use rand::Rng;
+use std::f32::consts::PI;
+
+use crate::{
+    components::{
+        aging::Age,
+        digestion::Fullness,
+        genetics::{Genome, InheritedGenome},
+        lineage::{LineageId, LineageParent},
+        reproduction::Reproduction,
+        utility::{ActiveBehavior, Behavior},
+    },
+    resources::{rng::SimRng, simulation_time::SimulationTime},
+    systems::spawner::CreatureSpawnEvent,
+};
+
+/// Offspring are placed at a random spot around their parent, between these distances.
+const BIRTH_DISTANCE: (f32, f32) = (0.5, 1.0);
+
+/// Creatures whose fullness exceeds their reproduction threshold pay the energy cost and spawn
+/// a litter of their own type next to them. The offspring inherit the genome of their parent.
+/// Creatures with `UtilityAi` only reproduce while they are set on mating.
+pub struct ReproductionSystem;
+
+impl<'s> System<'s> for ReproductionSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Reproduction>,
+        WriteStorage<'s, Fullness>,
+        ReadStorage<'s, Named>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Genome>,
+        ReadStorage<'s, LineageId>,
+        ReadStorage<'s, Age>,
+        ReadStorage<'s, ActiveBehavior>,
+        Read<'s, LazyUpdate>,
+        Write<'s, EventChannel<CreatureSpawnEvent>>,
+        Read<'s, SimulationTime>,
+        WriteExpect<'s, SimRng>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut reproductions,
+            mut fullnesses,
+            names,
+            transforms,
+            genomes,
+            lineage_ids,
+            ages,
+            active_behaviors,
+            lazy_update,
+            mut spawn_events,
+            time,
+            mut sim_rng,
+        ): Self::SystemData,
+    ) {
+        let rng = sim_rng.stream("reproduction");
+        for (entity, reproduction, fullness, name, transform) in (
+            &entities,
+            &mut reproductions,
+            &mut fullnesses,
+            &names,
+            &transforms,
+        )
+            .join()
+        {
+            reproduction.time_left = (reproduction.time_left - time.delta_seconds()).max(0.0);
+            if reproduction.time_left > 0.0 || fullness.value < reproduction.fullness_threshold {
+                continue;
+            }
+            match active_behaviors.get(entity) {
+                Some(ActiveBehavior(Behavior::Mate)) | None => (),
+                Some(_) => continue,
+            }
+            fullness.value -= reproduction.energy_cost;
+            reproduction.time_left = reproduction.cooldown;
+
+            for _ in 0..reproduction.litter_size {
+                let angle = rng.gen_range(0.0, 2.0 * PI);
+                let distance = rng.gen_range(BIRTH_DISTANCE.0, BIRTH_DISTANCE.1);
+                let mut offspring_transform = transform.clone();
+                offspring_transform.prepend_translation_x(distance * angle.cos());
+                offspring_transform.prepend_translation_y(distance * angle.sin());
+                // Offspring are born at the size of an adult, their own life stage shrinks them.
+                if let Some(adult_traits) =
+                    ages.get(entity).and_then(|age| age.adult_traits.as_ref())
+                {
+                    offspring_transform.set_scale(adult_traits.scale);
+                }
+
+                let mut offspring_builder = lazy_update
+                    .create_entity(&entities)
+                    .with(offspring_transform);
+                if let Some(genome) = genomes.get(entity) {
+                    offspring_builder = offspring_builder.with(InheritedGenome(genome.clone()));
+                }
+                if let Some(lineage_id) = lineage_ids.get(entity) {
+                    offspring_builder = offspring_builder.with(LineageParent(lineage_id.0));
+                }
+                spawn_events.single_write(CreatureSpawnEvent {
+                    creature_type: name.name.to_string(),
+                    entity: offspring_builder.build(),
+                    parent: Some(entity),
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use amethyst::{core::Named, ecs::*};
+
+use crate::utils::save_game::{PendingRestore, SavedStateData};
+
+/// Applies the saved state of loaded entities as soon as their prefab has been instantiated,
+/// which is when they get their `Named` component.
+pub struct RestoreSystem;
+impl<'s> System<'s> for RestoreSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, PendingRestore>,
+        ReadStorage<'s, Named>,
+        SavedStateData<'s>,
+    );
+
+    fn run(&mut self, (entities, mut pending_restores, names, mut state_data): Self::SystemData) {
+        let instantiated = (&entities, &pending_restores, &names)
+            .join()
+            .map(|(entity, _, _)| entity)
+            .collect::<Vec<Entity>>();
+        for entity in instantiated {
+            if let Some(PendingRestore(state)) = pending_restores.remove(entity) {
+                state.apply(entity, &mut state_data);
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use amethyst::{
+    core::{math::Vector3, transform::Transform, Named},
+    ecs::*,
+    shrev::EventChannel,
+};
+use rand::Rng;
+use std::f32::consts::PI;
+
+use crate::{
+    components::{
+        creatures::{FallingTag, Movement},
+        digestion::Fullness,
+        photosynthesis::Photosynthesis,
+        seeds::{Seed, SeedDispersal},
+    },
+    resources::{
+        rng::SimRng, simulation_time::SimulationTime, soil::SoilFertility,
+        spatial_grid::SpatialGrid, wind::Wind, world_bounds::WorldBounds,
+    },
+    systems::spawner::CreatureSpawnEvent,
+};
+
+/// Height above its plant at which a seed is released.
+const RELEASE_HEIGHT: f32 = 0.5;
+/// Seeds drift with the wind, but never faster than this.
+const MAX_SEED_SPEED: f32 = 10.0;
+
+/// Makes every plant with `SeedDispersal` release seeds into the wind at a regular interval, as
+/// long as it has the energy to spare. The seeds fall with the `GravitySystem`.
+pub struct SeedDispersalSystem;
+
+impl<'s> System<'s> for SeedDispersalSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, SeedDispersal>,
+        WriteStorage<'s, Fullness>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Named>,
+        Read<'s, LazyUpdate>,
+        Read<'s, Wind>,
+        Read<'s, SimulationTime>,
+        WriteExpect<'s, SimRng>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut seed_dispersals,
+            mut fullnesses,
+            transforms,
+            names,
+            lazy_update,
+            wind,
+            time,
+            mut sim_rng,
+        ): Self::SystemData,
+    ) {
+        let rng = sim_rng.stream("seeds");
+        for (dispersal, fullness, transform, name) in
+            (&mut seed_dispersals, &mut fullnesses, &transforms, &names).join()
+        {
+            dispersal.time_left -= time.delta_seconds();
+            if dispersal.time_left > 0.0 {
+                continue;
+            }
+            dispersal.time_left += dispersal.interval;
+
+            for _ in 0..dispersal.seeds_per_release {
+                if fullness.value - dispersal.energy_cost < dispersal.min_fullness {
+                    break;
+                }
+                fullness.value -= dispersal.energy_cost;
+
+                let mut seed_transform = Transform::default();
+                seed_transform.set_translation_xyz(
+                    transform.translation().x,
+                    transform.translation().y,
+                    RELEASE_HEIGHT,
+                );
+                let movement = Movement {
+                    velocity: Vector3::new(
+                        wind.wind.x,
+                        wind.wind.y,
+                        dispersal.launch_speed * rng.gen_range(0.5, 1.0),
+                    ),
+                    max_movement_speed: MAX_SEED_SPEED,
+                };
+                let seed = Seed {
+                    creature_type: name.name.to_string(),
+                    germination_chance: dispersal.germination_chance,
+                    crowding_radius: dispersal.crowding_radius,
+                    crowding_limit: dispersal.crowding_limit,
+                };
+                lazy_update
+                    .create_entity(&entities)
+                    .with(seed_transform)
+                    .with(movement)
+                    .with(FallingTag)
+                    .with(seed)
+                    .build();
+            }
+        }
+    }
+}
+
+/// Carries airborne seeds along the wind. A seed that lands inside the world bounds may grow into
+/// a new plant; the more plants already grow around it, the smaller its chance, and the more
+/// fertile the soil, the larger.
+pub struct GerminationSystem;
+
+impl<'s> System<'s> for GerminationSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Seed>,
+        WriteStorage<'s, Movement>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Photosynthesis>,
+        ReadExpect<'s, SpatialGrid>,
+        Read<'s, LazyUpdate>,
+        Write<'s, EventChannel<CreatureSpawnEvent>>,
+        Read<'s, Wind>,
+        Read<'s, WorldBounds>,
+        ReadExpect<'s, SoilFertility>,
+        WriteExpect<'s, SimRng>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            seeds,
+            mut movements,
+            transforms,
+            photosyntheses,
+            spatial_grid,
+            lazy_update,
+            mut spawn_events,
+            wind,
+            bounds,
+            soil,
+            mut sim_rng,
+        ): Self::SystemData,
+    ) {
+        let rng = sim_rng.stream("germination");
+        for (entity, seed, movement, transform) in
+            (&entities, &seeds, &mut movements, &transforms).join()
+        {
+            movement.velocity.x = wind.wind.x;
+            movement.velocity.y = wind.wind.y;
+            if transform.translation().z > 0.0 || movement.velocity.z > 0.0 {
+                continue;
+            }
+
+            // The seed landed.
+            let _ = entities.delete(entity);
+            let (x, y) = (transform.translation().x, transform.translation().y);
+            if x < bounds.left || x > bounds.right || y < bounds.bottom || y > bounds.top {
+                continue;
+            }
+            let nearby = spatial_grid.query(transform, seed.crowding_radius);
+            let neighbours = (&photosyntheses, &transforms, &nearby)
+                .join()
+                .filter(|(_, plant_transform, _)| {
+                    (plant_transform.translation().xy() - transform.translation().xy()).magnitude()
+                        < seed.crowding_radius
+                })
+                .count() as u32;
+            let chance = seed.germination_chance(neighbours) * soil.boost(x, y);
+            if rng.gen::<f32>() >= chance {
+                continue;
+            }
+
+            let mut plant_transform = Transform::default();
+            plant_transform.set_translation_xyz(x, y, 0.01);
+            plant_transform.set_rotation_euler(0.0, 0.0, rng.gen_range(0.0, PI));
+            spawn_events.single_write(CreatureSpawnEvent {
+                creature_type: seed.creature_type.clone(),
+                entity: lazy_update
+                    .create_entity(&entities)
+                    .with(plant_transform)
+                    .build(),
+                parent: Some(entity),
+            });
+        }
+    }
+}
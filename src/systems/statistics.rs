@@ -0,0 +1,171 @@
+use amethyst::{
+    core::Named,
+    ecs::*,
+    shrev::{EventChannel, ReaderId},
+};
+use std::collections::BTreeMap;
+
+use crate::{
+    components::{
+        combat::{HasFaction, Health},
+        creatures::CreatureTag,
+        digestion::Fullness,
+    },
+    resources::{
+        run_directory::RunDirectory,
+        simulation_time::SimulationTime,
+        statistics::{GroupStats, PopulationStats},
+    },
+    systems::{death::CreatureDeathEvent, spawner::CreatureSpawnEvent},
+};
+
+/// Counts births and deaths per creature type and samples the living population into the
+/// `PopulationStats` every `StatisticsConfig::interval` simulated seconds. Every sample is
+/// appended to the statistics file in the `RunDirectory`.
+#[derive(Default)]
+pub struct PopulationStatsSystem {
+    spawn_reader_id: Option<ReaderId<CreatureSpawnEvent>>,
+    death_reader_id: Option<ReaderId<CreatureDeathEvent>>,
+}
+
+/// Running totals of a group while a sample is taken.
+#[derive(Default)]
+struct GroupTotals {
+    count: u32,
+    fullness: (f32, u32),
+    health: (f32, u32),
+}
+
+impl GroupTotals {
+    fn add(&mut self, fullness: Option<&Fullness>, health: Option<&Health>) {
+        self.count += 1;
+        if let Some(fullness) = fullness {
+            self.fullness.0 += fullness.value;
+            self.fullness.1 += 1;
+        }
+        if let Some(health) = health {
+            self.health.0 += health.value;
+            self.health.1 += 1;
+        }
+    }
+
+    fn into_stats(self) -> GroupStats {
+        let average = |(sum, count): (f32, u32)| {
+            if count > 0 {
+                Some(sum / count as f32)
+            } else {
+                None
+            }
+        };
+        GroupStats {
+            count: self.count,
+            average_fullness: average(self.fullness),
+            average_health: average(self.health),
+            births: None,
+            deaths: None,
+        }
+    }
+}
+
+impl<'s> System<'s> for PopulationStatsSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Named>,
+        ReadStorage<'s, CreatureTag>,
+        ReadStorage<'s, HasFaction<Entity>>,
+        ReadStorage<'s, Fullness>,
+        ReadStorage<'s, Health>,
+        Read<'s, EventChannel<CreatureSpawnEvent>>,
+        Read<'s, EventChannel<CreatureDeathEvent>>,
+        Write<'s, PopulationStats>,
+        Read<'s, SimulationTime>,
+        ReadExpect<'s, RunDirectory>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        <Self as System<'_>>::SystemData::setup(world);
+        self.spawn_reader_id = Some(
+            world
+                .fetch_mut::<EventChannel<CreatureSpawnEvent>>()
+                .register_reader(),
+        );
+        self.death_reader_id = Some(
+            world
+                .fetch_mut::<EventChannel<CreatureDeathEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            names,
+            creature_tags,
+            factions,
+            fullnesses,
+            healths,
+            spawn_events,
+            death_events,
+            mut stats,
+            time,
+            run_directory,
+        ): Self::SystemData,
+    ) {
+        // Only offspring count as births, not founders, carcasses or other spawned entities.
+        for event in spawn_events
+            .read(self.spawn_reader_id.as_mut().unwrap())
+            .filter(|event| event.parent.is_some())
+        {
+            stats.record_birth(&event.creature_type);
+        }
+        for event in death_events.read(self.death_reader_id.as_mut().unwrap()) {
+            if let Some(name) = names.get(event.deceased) {
+                stats.record_death(&name.name);
+            }
+        }
+
+        stats.seconds_to_next_sample -= time.delta_seconds();
+        if stats.seconds_to_next_sample > 0.0 {
+            return;
+        }
+        stats.seconds_to_next_sample += stats.config.interval;
+
+        let mut creatures: BTreeMap<String, GroupTotals> = BTreeMap::new();
+        let mut faction_totals: BTreeMap<String, GroupTotals> = BTreeMap::new();
+        for (entity, name, _) in (&entities, &names, &creature_tags).join() {
+            let fullness = fullnesses.get(entity);
+            let health = healths.get(entity);
+            creatures
+                .entry(name.name.to_string())
+                .or_default()
+                .add(fullness, health);
+            if let Some(faction_name) = factions
+                .get(entity)
+                .and_then(|faction| names.get(faction.faction))
+            {
+                faction_totals
+                    .entry(faction_name.name.to_string())
+                    .or_default()
+                    .add(fullness, health);
+            }
+        }
+
+        let sample = stats.finish_sample(
+            time.elapsed_seconds(),
+            creatures
+                .into_iter()
+                .map(|(name, totals)| (name, totals.into_stats()))
+                .collect(),
+            faction_totals
+                .into_iter()
+                .map(|(name, totals)| (name, totals.into_stats()))
+                .collect(),
+        );
+
+        if let Err(error) = stats.write_sample(run_directory.path(), &sample) {
+            error!("Failed to write the population statistics: {}", error);
+        }
+        stats.latest = Some(sample);
+    }
+}
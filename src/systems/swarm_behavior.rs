@@ -1,14 +1,13 @@
 use amethyst::{
     core::{
         math::Vector3,
-        timing::Time,
         transform::components::{Parent, Transform},
     },
     ecs::*,
     shrev::EventChannel,
 };
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use std::f32;
 
 use crate::{
@@ -16,6 +15,7 @@ use crate::{
         creatures::{AvoidObstaclesTag, Movement, Wander},
         swarm::{SwarmBehavior, SwarmCenter},
     },
+    resources::{rng::SimRng, simulation_time::SimulationTime},
     systems::spawner::CreatureSpawnEvent,
 };
 
@@ -29,14 +29,18 @@ impl<'s> System<'s> for SwarmSpawnSystem {
         Entities<'s>,
         Read<'s, LazyUpdate>,
         Write<'s, EventChannel<CreatureSpawnEvent>>,
-        Read<'s, Time>,
+        Read<'s, SimulationTime>,
+        WriteExpect<'s, SimRng>,
     );
 
-    fn run(&mut self, (entities, lazy_update, mut spawn_events, time): Self::SystemData) {
+    fn run(
+        &mut self,
+        (entities, lazy_update, mut spawn_events, time, mut sim_rng): Self::SystemData,
+    ) {
         let delta_seconds = time.delta_seconds();
         self.swarm_timer -= delta_seconds;
         if self.swarm_timer <= 0.0 {
-            let mut rng = thread_rng();
+            let rng = sim_rng.stream("swarm");
             self.swarm_timer = 10.0f32;
             let mut swarm_entity_builder = lazy_update.create_entity(&entities);
             let x = rng.gen_range(-10.0, 10.0);
@@ -88,6 +92,7 @@ impl<'s> System<'s> for SwarmSpawnSystem {
                 spawn_events.single_write(CreatureSpawnEvent {
                     creature_type: "Ixie".to_string(),
                     entity: swarmling_entity,
+                    parent: None,
                 });
             }
             lazy_update.insert(swarm_entity, swarm_center);
@@ -101,7 +106,7 @@ pub struct SwarmCenterSystem {}
 impl<'s> System<'s> for SwarmCenterSystem {
     type SystemData = (
         Entities<'s>,
-        Read<'s, Time>,
+        Read<'s, SimulationTime>,
         WriteStorage<'s, SwarmCenter>,
         ReadStorage<'s, SwarmBehavior>,
     );
@@ -129,7 +134,7 @@ pub struct SwarmBehaviorSystem {}
 impl<'s> System<'s> for SwarmBehaviorSystem {
     type SystemData = (
         Entities<'s>,
-        Read<'s, Time>,
+        Read<'s, SimulationTime>,
         ReadStorage<'s, Transform>,
         ReadStorage<'s, SwarmCenter>,
         ReadStorage<'s, SwarmBehavior>,
@@ -141,43 +146,29 @@ impl<'s> System<'s> for SwarmBehaviorSystem {
         (_entities, time, transforms, _swarm_centers, swarm_behaviors, mut movements): Self::SystemData,
     ) {
         let delta_seconds = time.delta_seconds();
-
-        // avoid divide-by-zero when delta_seconds is zero
-        if delta_seconds <= f32::EPSILON {
-            return;
-        }
-        let time_step = 0.01;
-        let iterations = (delta_seconds / time_step) as u32 + 1;
         for (transform, swarm_behavior, mut movement) in
             (&transforms, &swarm_behaviors, &mut movements).join()
         {
             let original_position = transform.translation();
-            let mut current_position = original_position.clone();
-            let mut current_velocity = movement.velocity.clone();
+            let mut current_velocity = movement.velocity;
             let pull_factor = 10.0;
             let side_factor = 5.0;
-            for t in 0..iterations {
-                let iter_step = time_step.min(delta_seconds - time_step * t as f32);
-                let center_pull = if current_position.norm_squared() > 0.16 {
-                    swarm_behavior.attraction * pull_factor * (-current_position)
-                } else {
-                    Vector3::new(0.0, 0.0, 0.0)
-                };
-                let mut side_direction =
-                    Vector3::new(current_velocity[1], -current_velocity[0], 0.0);
-                if !(side_direction.norm_squared() < f32::EPSILON) {
-                    side_direction = side_direction.normalize();
-                }
-                let side_deviation_force = swarm_behavior.deviation * side_factor * side_direction;
-                let delta_velocity = iter_step * (center_pull + side_deviation_force);
-                current_velocity = current_velocity + delta_velocity;
-                let speed = current_velocity.norm();
-                if speed > movement.max_movement_speed {
-                    current_velocity *= movement.max_movement_speed / speed;
-                }
-                current_position = current_position + iter_step * current_velocity;
+            let center_pull = if original_position.norm_squared() > 0.16 {
+                swarm_behavior.attraction * pull_factor * (-original_position)
+            } else {
+                Vector3::new(0.0, 0.0, 0.0)
+            };
+            let mut side_direction = Vector3::new(current_velocity[1], -current_velocity[0], 0.0);
+            if !(side_direction.norm_squared() < f32::EPSILON) {
+                side_direction = side_direction.normalize();
+            }
+            let side_deviation_force = swarm_behavior.deviation * side_factor * side_direction;
+            current_velocity += delta_seconds * (center_pull + side_deviation_force);
+            let speed = current_velocity.norm();
+            if speed > movement.max_movement_speed {
+                current_velocity *= movement.max_movement_speed / speed;
             }
-            movement.velocity = (current_position - original_position) / delta_seconds;
+            movement.velocity = current_velocity;
         }
     }
 }
@@ -1,2 +1,3 @@
 pub mod hierarchy_util;
+pub mod save_game;
 pub mod spatial_hash;
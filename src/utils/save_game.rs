@@ -0,0 +1,465 @@
+use amethyst::{
+    assets::{Handle, Prefab},
+    config::Config,
+    core::{
+        transform::{Parent, Transform},
+        Named,
+    },
+    ecs::{hibitset::BitSetOr, prelude::*},
+    utils::application_root_dir,
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs};
+
+use crate::{
+    components::{
+        aging::Age,
+        collider::Circle,
+        combat::{Cooldown, Damage, Factions, HasFaction, Health, Lunge},
+        creatures::{AvoidObstaclesTag, Carcass, CreaturePrefabData, FallingTag, Movement, Wander},
+        decay::Decay,
+        digestion::{Digestion, Fullness, Nutrition},
+        genetics::Genome,
+        interpolation::InterpolatedTranslation,
+        lineage::LineageId,
+        memory::Memory,
+        perception::Perception,
+        reproduction::Reproduction,
+        seeds::{Seed, SeedDispersal},
+        swarm::{SwarmBehavior, SwarmCenter},
+        utility::UtilityAi,
+    },
+    resources::{
+        deaths::Deaths, lineage::LineageRegistry, prefabs::CreaturePrefabs, rng::SimRng,
+        simulation_time::SimulationTime, soil::SoilFertility, wind::Wind,
+        world_bounds::WorldBounds,
+    },
+    utils::hierarchy_util::delete_hierarchy,
+};
+
+/// Bump this whenever the layout of `SaveGame` changes, so old saves are rejected instead of
+/// being misread.
+pub const SAVE_GAME_VERSION: u32 = 2;
+
+/// Where the pause menu saves to and loads from, relative to the application root.
+const QUICKSAVE_PATH: &str = "saves/quicksave.ron";
+
+/// Everything needed to continue a simulation: its resources and every organism in it.
+/// Entities refer to each other by their index in `entities`.
+#[derive(Deserialize, Serialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub simulation_time: SimulationTime,
+    pub rng: SimRng,
+    pub wind: Wind,
+    pub world_bounds: WorldBounds,
+    pub lineage: LineageRegistry,
+    pub soil: SoilFertility,
+    pub entities: Vec<SavedEntity>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SavedEntity {
+    /// The creature prefab the entity was spawned from, if any.
+    pub prefab: Option<String>,
+    pub transform: Transform,
+    pub parent: Option<usize>,
+    pub swarm_center: Option<Vec<usize>>,
+    pub swarm_behavior: Option<SavedSwarmBehavior>,
+    pub state: SavedState,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SavedSwarmBehavior {
+    pub swarm_center: Option<usize>,
+    pub attraction: f32,
+    pub deviation: f32,
+}
+
+/// The components a prefab may set when it is instantiated. They are applied after the prefab,
+/// see `PendingRestore`. A missing component is removed from the entity.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SavedState {
+    pub movement: Option<Movement>,
+    pub wander: Option<Wander>,
+    pub health: Option<Health>,
+    pub fullness: Option<Fullness>,
+    pub nutrition: Option<Nutrition>,
+    pub cooldown: Option<Cooldown>,
+    pub lunge: Option<Lunge>,
+    /// Name of the faction, faction entities are looked up again when loading.
+    pub faction: Option<String>,
+    pub carcass: Option<Carcass>,
+    pub avoid_obstacles: bool,
+    pub falling: bool,
+    pub genome: Option<Genome>,
+    // Set by the prefab, but changed by the genome.
+    pub perception: Option<Perception>,
+    pub collider: Option<Circle>,
+    pub damage: Option<Damage>,
+    pub digestion: Option<Digestion>,
+    pub reproduction: Option<Reproduction>,
+    pub lineage_id: Option<LineageId>,
+    pub seed_dispersal: Option<SeedDispersal>,
+    pub seed: Option<Seed>,
+    pub age: Option<Age>,
+    pub decay: Option<Decay>,
+    pub utility: Option<UtilityAi>,
+    pub memory: Option<Memory>,
+}
+
+/// The storages `SavedState::apply` writes to.
+pub type SavedStateData<'s> = (
+    Read<'s, Factions>,
+    WriteStorage<'s, Movement>,
+    WriteStorage<'s, Wander>,
+    WriteStorage<'s, Health>,
+    WriteStorage<'s, Fullness>,
+    WriteStorage<'s, Nutrition>,
+    WriteStorage<'s, Cooldown>,
+    WriteStorage<'s, Lunge>,
+    WriteStorage<'s, HasFaction<Entity>>,
+    WriteStorage<'s, Carcass>,
+    WriteStorage<'s, AvoidObstaclesTag>,
+    WriteStorage<'s, FallingTag>,
+    WriteStorage<'s, Genome>,
+    WriteStorage<'s, Perception>,
+    WriteStorage<'s, Circle>,
+    WriteStorage<'s, Damage>,
+    WriteStorage<'s, Digestion>,
+    WriteStorage<'s, Reproduction>,
+    WriteStorage<'s, LineageId>,
+    WriteStorage<'s, SeedDispersal>,
+    WriteStorage<'s, Seed>,
+    WriteStorage<'s, Age>,
+    WriteStorage<'s, Decay>,
+    WriteStorage<'s, UtilityAi>,
+    WriteStorage<'s, Memory>,
+);
+
+impl SavedState {
+    pub fn apply(&self, entity: Entity, data: &mut SavedStateData) {
+        let (
+            factions,
+            movements,
+            wanders,
+            healths,
+            fullnesses,
+            nutritions,
+            cooldowns,
+            lunges,
+            has_factions,
+            carcasses,
+            avoid_obstacles_tags,
+            falling_tags,
+            genomes,
+            perceptions,
+            circles,
+            damages,
+            digestions,
+            reproductions,
+            lineage_ids,
+            seed_dispersals,
+            seeds,
+            ages,
+            decays,
+            utilities,
+            memories,
+        ) = data;
+        restore(movements, entity, self.movement.clone());
+        restore(wanders, entity, self.wander);
+        restore(healths, entity, self.health.clone());
+        restore(fullnesses, entity, self.fullness.clone());
+        restore(nutritions, entity, self.nutrition.clone());
+        restore(cooldowns, entity, self.cooldown.clone());
+        restore(lunges, entity, self.lunge.clone());
+        let faction = self.faction.as_ref().and_then(|name| {
+            let faction = factions.get(name);
+            if faction.is_none() {
+                error!("Failed to find saved faction {:?}", name);
+            }
+            faction
+        });
+        restore(
+            has_factions,
+            entity,
+            faction.map(|faction| HasFaction { faction }),
+        );
+        restore(carcasses, entity, self.carcass.clone());
+        restore(
+            avoid_obstacles_tags,
+            entity,
+            Some(AvoidObstaclesTag).filter(|_| self.avoid_obstacles),
+        );
+        restore(
+            falling_tags,
+            entity,
+            Some(FallingTag).filter(|_| self.falling),
+        );
+        restore(genomes, entity, self.genome.clone());
+        restore(perceptions, entity, self.perception.clone());
+        restore(circles, entity, self.collider.clone());
+        restore(damages, entity, self.damage.clone());
+        restore(digestions, entity, self.digestion.clone());
+        restore(reproductions, entity, self.reproduction.clone());
+        restore(lineage_ids, entity, self.lineage_id);
+        restore(seed_dispersals, entity, self.seed_dispersal.clone());
+        restore(seeds, entity, self.seed.clone());
+        restore(ages, entity, self.age.clone());
+        restore(decays, entity, self.decay.clone());
+        restore(utilities, entity, self.utility.clone());
+        restore(memories, entity, self.memory.clone());
+    }
+}
+
+fn restore<T: Component>(storage: &mut WriteStorage<T>, entity: Entity, value: Option<T>) {
+    match value {
+        Some(value) => {
+            storage
+                .insert(entity, value)
+                .expect("unreachable, the entity was created while loading");
+        }
+        None => {
+            storage.remove(entity);
+        }
+    }
+}
+
+/// Saved state of a loaded entity, waiting for its prefab to be instantiated. Instantiating a
+/// prefab overwrites the components with their initial values, so the state can only be applied
+/// afterwards, by the `RestoreSystem`.
+pub struct PendingRestore(pub SavedState);
+
+impl Component for PendingRestore {
+    type Storage = HashMapStorage<Self>;
+}
+
+// Entities spawned from a creature prefab, swarm centers and airborne seeds make up the simulated
+// world. Anything else, like the camera, lights, factions or ui, is left alone.
+fn saved_entities(world: &World) -> Vec<Entity> {
+    let prefab_handles = world.read_storage::<Handle<Prefab<CreaturePrefabData>>>();
+    let swarm_centers = world.read_storage::<SwarmCenter>();
+    let seeds = world.read_storage::<Seed>();
+    (
+        &world.entities(),
+        BitSetOr(
+            BitSetOr(prefab_handles.mask(), swarm_centers.mask()),
+            seeds.mask(),
+        ),
+    )
+        .join()
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+impl SaveGame {
+    pub fn capture(world: &World) -> SaveGame {
+        let entities = saved_entities(world);
+        let ids: HashMap<Entity, usize> = entities
+            .iter()
+            .enumerate()
+            .map(|(id, entity)| (*entity, id))
+            .collect();
+
+        let names = world.read_storage::<Named>();
+        let prefab_handles = world.read_storage::<Handle<Prefab<CreaturePrefabData>>>();
+        let transforms = world.read_storage::<Transform>();
+        let interpolated = world.read_storage::<InterpolatedTranslation>();
+        let parents = world.read_storage::<Parent>();
+        let swarm_centers = world.read_storage::<SwarmCenter>();
+        let swarm_behaviors = world.read_storage::<SwarmBehavior>();
+        let movements = world.read_storage::<Movement>();
+        let wanders = world.read_storage::<Wander>();
+        let healths = world.read_storage::<Health>();
+        let fullnesses = world.read_storage::<Fullness>();
+        let nutritions = world.read_storage::<Nutrition>();
+        let cooldowns = world.read_storage::<Cooldown>();
+        let lunges = world.read_storage::<Lunge>();
+        let has_factions = world.read_storage::<HasFaction<Entity>>();
+        let carcasses = world.read_storage::<Carcass>();
+        let avoid_obstacles_tags = world.read_storage::<AvoidObstaclesTag>();
+        let falling_tags = world.read_storage::<FallingTag>();
+        let genomes = world.read_storage::<Genome>();
+        let perceptions = world.read_storage::<Perception>();
+        let circles = world.read_storage::<Circle>();
+        let damages = world.read_storage::<Damage>();
+        let digestions = world.read_storage::<Digestion>();
+        let reproductions = world.read_storage::<Reproduction>();
+        let lineage_ids = world.read_storage::<LineageId>();
+        let seed_dispersals = world.read_storage::<SeedDispersal>();
+        let seeds = world.read_storage::<Seed>();
+        let ages = world.read_storage::<Age>();
+        let decays = world.read_storage::<Decay>();
+        let utilities = world.read_storage::<UtilityAi>();
+        let memories = world.read_storage::<Memory>();
+
+        let saved = entities
+            .iter()
+            .map(|entity| {
+                let entity = *entity;
+                let prefab = prefab_handles
+                    .get(entity)
+                    .and_then(|_| names.get(entity))
+                    .map(|named| named.name.to_string());
+                let mut transform = transforms.get(entity).cloned().unwrap_or_default();
+                // The rendered translation is blended between ticks, save the simulated one.
+                if let Some(interpolated) = interpolated.get(entity) {
+                    transform.set_translation(interpolated.current);
+                }
+                SavedEntity {
+                    prefab,
+                    transform,
+                    parent: parents
+                        .get(entity)
+                        .and_then(|parent| ids.get(&parent.entity).cloned()),
+                    swarm_center: swarm_centers.get(entity).map(|swarm_center| {
+                        swarm_center
+                            .entities
+                            .iter()
+                            .filter_map(|swarmling| ids.get(swarmling).cloned())
+                            .collect()
+                    }),
+                    swarm_behavior: swarm_behaviors.get(entity).map(|swarm_behavior| {
+                        SavedSwarmBehavior {
+                            swarm_center: swarm_behavior
+                                .swarm_center
+                                .and_then(|center| ids.get(&center).cloned()),
+                            attraction: swarm_behavior.attraction,
+                            deviation: swarm_behavior.deviation,
+                        }
+                    }),
+                    state: SavedState {
+                        movement: movements.get(entity).cloned(),
+                        wander: wanders.get(entity).cloned(),
+                        health: healths.get(entity).cloned(),
+                        fullness: fullnesses.get(entity).cloned(),
+                        nutrition: nutritions.get(entity).cloned(),
+                        cooldown: cooldowns.get(entity).cloned(),
+                        lunge: lunges.get(entity).cloned(),
+                        faction: has_factions
+                            .get(entity)
+                            .and_then(|has_faction| names.get(has_faction.faction))
+                            .map(|named| named.name.to_string()),
+                        carcass: carcasses.get(entity).cloned(),
+                        avoid_obstacles: avoid_obstacles_tags.contains(entity),
+                        falling: falling_tags.contains(entity),
+                        genome: genomes.get(entity).cloned(),
+                        perception: perceptions.get(entity).cloned(),
+                        collider: circles.get(entity).cloned(),
+                        damage: damages.get(entity).cloned(),
+                        digestion: digestions.get(entity).cloned(),
+                        reproduction: reproductions.get(entity).cloned(),
+                        lineage_id: lineage_ids.get(entity).cloned(),
+                        seed_dispersal: seed_dispersals.get(entity).cloned(),
+                        seed: seeds.get(entity).cloned(),
+                        age: ages.get(entity).cloned(),
+                        decay: decays.get(entity).cloned(),
+                        utility: utilities.get(entity).cloned(),
+                        memory: memories.get(entity).cloned(),
+                    },
+                }
+            })
+            .collect();
+
+        SaveGame {
+            version: SAVE_GAME_VERSION,
+            simulation_time: world.read_resource::<SimulationTime>().clone(),
+            rng: world.read_resource::<SimRng>().clone(),
+            wind: world.read_resource::<Wind>().clone(),
+            world_bounds: world.read_resource::<WorldBounds>().clone(),
+            lineage: world.read_resource::<LineageRegistry>().clone(),
+            soil: world.read_resource::<SoilFertility>().clone(),
+            entities: saved,
+        }
+    }
+
+    /// Replaces the simulated world with the saved one.
+    pub fn restore(self, world: &mut World) -> Result<(), Error> {
+        if self.version != SAVE_GAME_VERSION {
+            return Err(Error::from_string(format!(
+                "save game version {} is not supported, expected version {}",
+                self.version, SAVE_GAME_VERSION
+            )));
+        }
+
+        for entity in saved_entities(world) {
+            // Children, like the nodes of a gltf scene, may already be gone with their parent.
+            let _ = delete_hierarchy(entity, world);
+        }
+
+        world.insert(self.simulation_time);
+        world.insert(Deaths::default());
+        world.insert(self.rng);
+        world.insert(self.wind);
+        world.insert(self.world_bounds);
+        world.insert(self.lineage);
+        world.insert(self.soil);
+
+        let entities: Vec<Entity> = self
+            .entities
+            .iter()
+            .map(|saved| world.create_entity().with(saved.transform.clone()).build())
+            .collect();
+
+        let prefabs = world.read_resource::<CreaturePrefabs>();
+        let mut prefab_handles = world.write_storage::<Handle<Prefab<CreaturePrefabData>>>();
+        let mut parents = world.write_storage::<Parent>();
+        let mut swarm_centers = world.write_storage::<SwarmCenter>();
+        let mut swarm_behaviors = world.write_storage::<SwarmBehavior>();
+        let mut pending_restores = world.write_storage::<PendingRestore>();
+        let mut state_data = world.system_data::<SavedStateData>();
+        for (saved, entity) in self.entities.into_iter().zip(entities.iter().cloned()) {
+            if let Some(parent) = saved.parent {
+                parents.insert(entity, Parent::new(entities[parent]))?;
+            }
+            if let Some(swarmlings) = saved.swarm_center {
+                let swarm_center = SwarmCenter {
+                    entities: swarmlings.into_iter().map(|id| entities[id]).collect(),
+                };
+                swarm_centers.insert(entity, swarm_center)?;
+            }
+            if let Some(swarm_behavior) = saved.swarm_behavior {
+                let swarm_behavior = SwarmBehavior {
+                    swarm_center: swarm_behavior.swarm_center.map(|id| entities[id]),
+                    attraction: swarm_behavior.attraction,
+                    deviation: swarm_behavior.deviation,
+                };
+                swarm_behaviors.insert(entity, swarm_behavior)?;
+            }
+            match saved.prefab {
+                Some(prefab) => match prefabs.get_prefab(&prefab) {
+                    Some(handle) => {
+                        prefab_handles.insert(entity, handle.clone())?;
+                        pending_restores.insert(entity, PendingRestore(saved.state))?;
+                    }
+                    None => error!("Failed to find saved creature prefab {:?}", prefab),
+                },
+                None => saved.state.apply(entity, &mut state_data),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn quicksave_path() -> Result<std::path::PathBuf, Error> {
+    Ok(application_root_dir()?.join(QUICKSAVE_PATH))
+}
+
+pub fn save_game(world: &World) -> Result<(), Error> {
+    let path = quicksave_path()?;
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    SaveGame::capture(world).write(&path)?;
+    info!("Saved the game to {:?}", path);
+    Ok(())
+}
+
+pub fn load_game(world: &mut World) -> Result<(), Error> {
+    let path = quicksave_path()?;
+    SaveGame::load(&path)?.restore(world)?;
+    info!("Loaded the game from {:?}", path);
+    Ok(())
+}